@@ -14,10 +14,147 @@ use std::time::Duration;
 use channel::{build_vk_plain, prove_plain, verify_plain};
 
 use deep_ali::fri::{
-    deep_fri_prove, deep_fri_proof_size_bytes, deep_fri_verify, AliA, AliE, AliS, AliT,
-    DeepAliRealBuilder, DeepFriParams, DeepFriProof,
+    deep_fri_prove, deep_fri_proof_size_bytes, deep_fri_verify, deep_fri_verify_batch, queries_for_security,
+    AliA, AliE, AliS, AliT, DeepAliRealBuilder, DeepFriParams, DeepFriProof,
 };
 
+// ---------------------
+// Hardware fingerprint
+// ---------------------
+//
+// Prove/verify timings are meaningless across machines without knowing what
+// produced them, so before the bench loop runs we probe what we can cheaply learn
+// about the box (core count, RAM, a rough CPU model string) and run two
+// micro-benchmarks whose ratio to a hardcoded reference machine gives a single
+// normalized "machine score" other runs can divide their deltas-vs-paper by. This
+// is a hand-rolled stand-in for a `sysinfo`-style probe, not a new dependency.
+mod hw_fingerprint {
+    use ark_ff::UniformRand;
+    use ark_pallas::Fr as F;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::time::Instant;
+
+    #[derive(Clone)]
+    pub struct HwFingerprint {
+        pub cpu_model: String,
+        pub cores: usize,
+        pub mem_gb: f64,
+        pub field_mops: f64,
+        pub mem_gbps: f64,
+        pub machine_score: f64,
+    }
+
+    // Reference machine the score is normalized against -- roughly a mid-range
+    // 2023-era desktop core. Arbitrary but fixed, so scores are comparable across
+    // runs even as the reference itself ages.
+    const REF_FIELD_MOPS: f64 = 50.0;
+    const REF_MEM_GBPS: f64 = 10.0;
+
+    fn logical_cores() -> usize {
+        std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+    }
+
+    // Best-effort `/proc/cpuinfo` scrape; returns `None` off Linux or if the file
+    // isn't readable, in which case callers fall back to logical_cores()/"unknown".
+    fn proc_cpuinfo() -> Option<String> {
+        std::fs::read_to_string("/proc/cpuinfo").ok()
+    }
+
+    fn cpu_model() -> String {
+        proc_cpuinfo()
+            .and_then(|text| {
+                text.lines()
+                    .find(|l| l.starts_with("model name"))
+                    .and_then(|l| l.split(':').nth(1))
+                    .map(|s| s.trim().to_string())
+            })
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+
+    // Counts distinct (physical id, core id) pairs; falls back to logical core
+    // count if the fields aren't present (e.g. non-Linux, or a sandboxed /proc).
+    fn physical_cores() -> usize {
+        let Some(text) = proc_cpuinfo() else {
+            return logical_cores();
+        };
+        let mut cur_phys: Option<&str> = None;
+        let mut pairs = std::collections::HashSet::new();
+        for line in text.lines() {
+            if let Some(v) = line.strip_prefix("physical id") {
+                cur_phys = v.split(':').nth(1).map(|s| s.trim());
+            } else if let Some(v) = line.strip_prefix("core id") {
+                if let (Some(phys), Some(core)) = (cur_phys, v.split(':').nth(1)) {
+                    pairs.insert((phys.to_string(), core.trim().to_string()));
+                }
+            }
+        }
+        if pairs.is_empty() {
+            logical_cores()
+        } else {
+            pairs.len()
+        }
+    }
+
+    fn total_mem_gb() -> f64 {
+        let Some(text) = std::fs::read_to_string("/proc/meminfo").ok() else {
+            return 0.0;
+        };
+        text.lines()
+            .find(|l| l.starts_with("MemTotal:"))
+            .and_then(|l| l.split_whitespace().nth(1))
+            .and_then(|kb| kb.parse::<f64>().ok())
+            .map(|kb| kb / (1024.0 * 1024.0))
+            .unwrap_or(0.0)
+    }
+
+    // Micro-probe: time a batch of field multiplies, report throughput in
+    // millions of ops per second.
+    fn field_mul_mops() -> f64 {
+        let mut rng = StdRng::seed_from_u64(0xF1E1D_5CA1E);
+        let n = 2_000_000usize;
+        let xs: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+        let mut acc = F::from(1u64);
+        let t0 = Instant::now();
+        for &x in &xs {
+            acc *= x;
+        }
+        let elapsed = t0.elapsed().as_secs_f64();
+        criterion::black_box(acc);
+        (n as f64) / elapsed / 1.0e6
+    }
+
+    // Micro-probe: time a large buffer copy, report bandwidth in GB/s.
+    fn mem_copy_gbps() -> f64 {
+        let len = 64 * 1024 * 1024; // 64 MiB
+        let src = vec![0x5au8; len];
+        let mut dst = vec![0u8; len];
+        let t0 = Instant::now();
+        dst.copy_from_slice(&src);
+        let elapsed = t0.elapsed().as_secs_f64();
+        criterion::black_box(&dst);
+        let gb = (len as f64) / 1.0e9;
+        gb / elapsed
+    }
+
+    pub fn probe() -> HwFingerprint {
+        let field_mops = field_mul_mops();
+        let mem_gbps = mem_copy_gbps();
+        // Equal-weighted geometric mean of the two probes relative to the
+        // reference machine: a box twice as fast on both axes scores 2.0.
+        let machine_score =
+            ((field_mops / REF_FIELD_MOPS) * (mem_gbps / REF_MEM_GBPS)).sqrt();
+
+        HwFingerprint {
+            cpu_model: cpu_model(),
+            cores: physical_cores(),
+            mem_gb: total_mem_gb(),
+            field_mops,
+            mem_gbps,
+            machine_score,
+        }
+    }
+}
+
 // ---------------------
 // CSV record
 // ---------------------
@@ -36,15 +173,22 @@ struct CsvRow {
     delta_prove_pct: f64,
     delta_verify_pct: f64,
     delta_throughput_pct: f64,
+    // hardware fingerprint (constant across rows in a run)
+    cpu_model: String,
+    cores: usize,
+    mem_gb: f64,
+    field_mops: f64,
+    mem_gbps: f64,
+    machine_score: f64,
 }
 
 impl CsvRow {
     fn header() -> &'static str {
-        "csv,label,k,schedule,proof_bytes,prove_s,verify_ms,prove_elems_per_s,delta_size_pct_vs_paper,delta_prove_pct_vs_paper,delta_verify_pct_vs_paper,delta_throughput_pct_vs_paper"
+        "csv,label,k,schedule,proof_bytes,prove_s,verify_ms,prove_elems_per_s,delta_size_pct_vs_paper,delta_prove_pct_vs_paper,delta_verify_pct_vs_paper,delta_throughput_pct_vs_paper,cpu_model,cores,mem_gb,field_mops,mem_gbps,machine_score"
     }
     fn to_line(&self) -> String {
         format!(
-            "csv,{},{},{},{},{:.6},{:.3},{:.6},{:.2},{:.2},{:.2},{:.2}\n",
+            "csv,{},{},{},{},{:.6},{:.3},{:.6},{:.2},{:.2},{:.2},{:.2},{},{},{:.2},{:.2},{:.2},{:.3}\n",
             self.label,
             self.k,
             self.schedule,
@@ -55,13 +199,19 @@ impl CsvRow {
             self.delta_size_pct,
             self.delta_prove_pct,
             self.delta_verify_pct,
-            self.delta_throughput_pct
+            self.delta_throughput_pct,
+            self.cpu_model,
+            self.cores,
+            self.mem_gb,
+            self.field_mops,
+            self.mem_gbps,
+            self.machine_score
         )
     }
     fn print_stdout(&self) {
         // Also print to stdout (without trailing newline because we add \n in to_line)
         print!(
-            "csv,{},{},{},{},{:.6},{:.3},{:.6},{:.2},{:.2},{:.2},{:.2}\n",
+            "csv,{},{},{},{},{:.6},{:.3},{:.6},{:.2},{:.2},{:.2},{:.2},{},{},{:.2},{:.2},{:.2},{:.3}\n",
             self.label,
             self.k,
             self.schedule,
@@ -72,11 +222,127 @@ impl CsvRow {
             self.delta_size_pct,
             self.delta_prove_pct,
             self.delta_verify_pct,
-            self.delta_throughput_pct
+            self.delta_throughput_pct,
+            self.cpu_model,
+            self.cores,
+            self.mem_gb,
+            self.field_mops,
+            self.mem_gbps,
+            self.machine_score
         );
     }
 }
 
+// ---------------------
+// Arrow/Parquet export (optional)
+// ---------------------
+//
+// `CsvRow::to_line` renders floats through `{:.6}`/`{:.3}`, which is fine for the
+// human-readable CSV but loses precision a downstream analysis notebook might care
+// about, and gives it nothing typed to query against. Behind `arrow_export`, mirror
+// the whole run into a single Arrow `RecordBatch` (one batch per preset/k sweep, not
+// per row -- columnar formats amortize over a whole run, not a single line) and flush
+// it to Parquet alongside the CSV, exactly the way `erasure_coding` sits next to the
+// always-on code path in `deep_ali::fri` rather than replacing it.
+#[cfg(feature = "arrow_export")]
+mod arrow_export {
+    use super::CsvRow;
+    use arrow::array::{Float64Array, StringArray, UInt64Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use std::sync::Arc;
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("label", DataType::Utf8, false),
+            Field::new("schedule", DataType::Utf8, false),
+            Field::new("k", DataType::UInt64, false),
+            Field::new("proof_bytes", DataType::UInt64, false),
+            Field::new("prove_s", DataType::Float64, false),
+            Field::new("verify_ms", DataType::Float64, false),
+            Field::new("prove_elems_per_s", DataType::Float64, false),
+            Field::new("delta_size_pct", DataType::Float64, false),
+            Field::new("delta_prove_pct", DataType::Float64, false),
+            Field::new("delta_verify_pct", DataType::Float64, false),
+            Field::new("delta_throughput_pct", DataType::Float64, false),
+            Field::new("cpu_model", DataType::Utf8, false),
+            Field::new("cores", DataType::UInt64, false),
+            Field::new("mem_gb", DataType::Float64, false),
+            Field::new("field_mops", DataType::Float64, false),
+            Field::new("mem_gbps", DataType::Float64, false),
+            Field::new("machine_score", DataType::Float64, false),
+        ])
+    }
+
+    fn to_record_batch(rows: &[CsvRow]) -> RecordBatch {
+        let label: StringArray = rows.iter().map(|r| Some(r.label.as_str())).collect();
+        let schedule: StringArray = rows.iter().map(|r| Some(r.schedule.as_str())).collect();
+        let k: UInt64Array = rows.iter().map(|r| Some(r.k as u64)).collect();
+        let proof_bytes: UInt64Array = rows.iter().map(|r| Some(r.proof_bytes as u64)).collect();
+        let prove_s: Float64Array = rows.iter().map(|r| Some(r.prove_s)).collect();
+        let verify_ms: Float64Array = rows.iter().map(|r| Some(r.verify_ms)).collect();
+        let prove_elems_per_s: Float64Array =
+            rows.iter().map(|r| Some(r.prove_elems_per_s)).collect();
+        let delta_size_pct: Float64Array = rows.iter().map(|r| Some(r.delta_size_pct)).collect();
+        let delta_prove_pct: Float64Array = rows.iter().map(|r| Some(r.delta_prove_pct)).collect();
+        let delta_verify_pct: Float64Array =
+            rows.iter().map(|r| Some(r.delta_verify_pct)).collect();
+        let delta_throughput_pct: Float64Array =
+            rows.iter().map(|r| Some(r.delta_throughput_pct)).collect();
+        let cpu_model: StringArray = rows.iter().map(|r| Some(r.cpu_model.as_str())).collect();
+        let cores: UInt64Array = rows.iter().map(|r| Some(r.cores as u64)).collect();
+        let mem_gb: Float64Array = rows.iter().map(|r| Some(r.mem_gb)).collect();
+        let field_mops: Float64Array = rows.iter().map(|r| Some(r.field_mops)).collect();
+        let mem_gbps: Float64Array = rows.iter().map(|r| Some(r.mem_gbps)).collect();
+        let machine_score: Float64Array = rows.iter().map(|r| Some(r.machine_score)).collect();
+
+        RecordBatch::try_new(
+            Arc::new(schema()),
+            vec![
+                Arc::new(label),
+                Arc::new(schedule),
+                Arc::new(k),
+                Arc::new(proof_bytes),
+                Arc::new(prove_s),
+                Arc::new(verify_ms),
+                Arc::new(prove_elems_per_s),
+                Arc::new(delta_size_pct),
+                Arc::new(delta_prove_pct),
+                Arc::new(delta_verify_pct),
+                Arc::new(delta_throughput_pct),
+                Arc::new(cpu_model),
+                Arc::new(cores),
+                Arc::new(mem_gb),
+                Arc::new(field_mops),
+                Arc::new(mem_gbps),
+                Arc::new(machine_score),
+            ],
+        )
+        .expect("CsvRow column arrays have mismatched lengths")
+    }
+
+    /// Flushes every row gathered this run into a single SNAPPY-compressed Parquet
+    /// file at `path`, schema-on, so notebooks can query it directly instead of
+    /// re-parsing the CSV's `{:.6}`-truncated floats.
+    pub fn write_parquet(path: &str, rows: &[CsvRow]) {
+        if rows.is_empty() {
+            return;
+        }
+        let batch = to_record_batch(rows);
+        let file = std::fs::File::create(path)
+            .unwrap_or_else(|e| panic!("failed to create {path}: {e}"));
+        let props = WriterProperties::builder()
+            .set_compression(parquet::basic::Compression::SNAPPY)
+            .build();
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props))
+            .expect("failed to construct ArrowWriter");
+        writer.write(&batch).expect("failed to write record batch");
+        writer.close().expect("failed to finalize parquet file");
+    }
+}
+
 // ---------------------
 // Schedule helpers
 // ---------------------
@@ -192,30 +458,70 @@ fn bench_e2e_mf_fri(c: &mut Criterion) {
     // bump high end so schedules with 128 have room (k must be ≥ 7 + …)
     let k_hi = 19usize;
 
-    // Presets: keep "paper" first so baseline is available for all ks
-    let presets: &[(&str, &[usize])] = &[
-        ("paper", &[16, 16, 8]),
-        ("mod16", &[16, 16, 16, 16]),
-        ("uni32x3", &[32, 32, 32]),
-        ("uni64x2x8", &[64, 64, 8]),
-        ("hi64_32_8", &[64, 32, 8]),
-        ("hi32_32_16", &[32, 32, 16]),
+    // Assumed Reed-Solomon rate behind the above `r = 32` baseline, and the query
+    // security target it's meant to hit (`32 * log2(1/rate) ≈ 96`, close enough to 100
+    // for the grinding presets below to land on a comparable `r`) -- this bench doesn't
+    // otherwise track a literal blowup factor, so these are illustrative assumptions,
+    // not read out of `DeepFriParams` itself.
+    const ASSUMED_RATE: f64 = 1.0 / 8.0;
+    const TARGET_SECURITY_BITS: u32 = 100;
+
+    // Presets: keep "paper" first so baseline is available for all ks. Third field is
+    // the forced Merkle commitment arity for every FRI layer (`None` = auto-pick, see
+    // `pick_arity_for_layer`); the `_poseidon2`/`_poseidon4` variants reuse "paper"'s
+    // schedule so their deltas isolate the cost of the leaner, arithmetization-friendly
+    // commitment from any schedule-shape effects. Fourth field is `pow_bits`: `0` uses
+    // the global `r` above unchanged; nonzero trades grinding work for fewer queries
+    // via `queries_for_security(TARGET_SECURITY_BITS, ASSUMED_RATE, pow_bits)`, so the
+    // `paper_pow*` presets isolate the proof-size/verify-time savings from replacing
+    // queries with grinding at a fixed security target.
+    let presets: &[(&str, &[usize], Option<usize>, u32)] = &[
+        ("paper", &[16, 16, 8], None, 0),
+        ("mod16", &[16, 16, 16, 16], None, 0),
+        ("uni32x3", &[32, 32, 32], None, 0),
+        ("uni64x2x8", &[64, 64, 8], None, 0),
+        ("hi64_32_8", &[64, 32, 8], None, 0),
+        ("hi32_32_16", &[32, 32, 16], None, 0),
         // New: schedules using 128-fold layers
-        ("uni128", &[128]),
-        ("uni128x2", &[128, 128]),
-        ("hi128_64", &[128, 64]),
-        ("hi128_32", &[128, 32]),
-        ("hi128_16", &[128, 16]),
-        ("hi128_64_8", &[128, 64, 8]),
-        ("hi128_32_8", &[128, 32, 8]),
+        ("uni128", &[128], None, 0),
+        ("uni128x2", &[128, 128], None, 0),
+        ("hi128_64", &[128, 64], None, 0),
+        ("hi128_32", &[128, 32], None, 0),
+        ("hi128_16", &[128, 16], None, 0),
+        ("hi128_64_8", &[128, 64, 8], None, 0),
+        ("hi128_32_8", &[128, 32, 8], None, 0),
+        // Poseidon field-Merkle commitment, forced to a small arity instead of the
+        // auto-picked wide tree -- reports the proof-size/prove-time cost of leaving
+        // openings cheap enough for a future in-circuit recursive verifier.
+        ("paper_poseidon2", &[16, 16, 8], Some(2), 0),
+        ("paper_poseidon4", &[16, 16, 8], Some(4), 0),
+        // Grinding presets: "paper"'s schedule/commitment, with `r` shrunk to whatever
+        // `queries_for_security` says still hits `TARGET_SECURITY_BITS` once `pow_bits`
+        // of grinding are added.
+        ("paper_pow16", &[16, 16, 8], None, 16),
+        ("paper_pow24", &[16, 16, 8], None, 24),
     ];
 
     // Deterministic input generation
     let mut rng_seed = 1337u64;
 
+    // Hardware fingerprint + machine score, constant for the whole run: lets rows
+    // gathered on different boxes be compared and the deltas-vs-paper below
+    // normalized against the machine that produced them.
+    let hw = hw_fingerprint::probe();
+    eprintln!(
+        "hw fingerprint: cpu={} cores={} mem={:.1}GB field_mops={:.1} mem_gbps={:.2} machine_score={:.3}",
+        hw.cpu_model, hw.cores, hw.mem_gb, hw.field_mops, hw.mem_gbps, hw.machine_score
+    );
+
     // Store per-k baseline (paper) for delta computation
     let mut paper_baseline: HashMap<usize, CsvRow> = HashMap::new();
 
+    // Every row this run produces, in emission order -- handed to `arrow_export`
+    // below as one `RecordBatch` per run (not per-row: Parquet and Arrow IPC both
+    // amortize better over a whole sweep than row-at-a-time writes would).
+    let mut all_rows: Vec<CsvRow> = Vec::new();
+
     // Prepare CSV file: truncate and write header once
     let file = File::create("benchmarkdata.csv")
         .expect("failed to create benchmarkdata.csv for writing");
@@ -226,7 +532,7 @@ fn bench_e2e_mf_fri(c: &mut Criterion) {
     // Also print header to stdout
     println!("{}", CsvRow::header());
 
-    for &(label, schedule) in presets {
+    for &(label, schedule, commitment_arity, pow_bits) in presets {
         let ks = ks_for_schedule(schedule, k_lo, k_hi);
         if ks.is_empty() {
             eprintln!(
@@ -251,22 +557,34 @@ fn bench_e2e_mf_fri(c: &mut Criterion) {
             let s: AliS = (0..n0).map(|_| F::rand(&mut rng)).collect();
             let e: AliE = (0..n0).map(|_| F::rand(&mut rng)).collect();
             let t: AliT = (0..n0).map(|_| F::rand(&mut rng)).collect();
+            // Single constraint-quotient column Φ = A·S + E − T, same shape as the
+            // old fixed-4-input merge; `build_f0` now takes an arbitrary column list.
+            let phi: Vec<F> = (0..n0).map(|i| a[i] * s[i] + e[i] - t[i]).collect();
+            let columns = [phi];
+
+            let r = if pow_bits > 0 {
+                queries_for_security(TARGET_SECURITY_BITS, ASSUMED_RATE, pow_bits)
+            } else {
+                r
+            };
 
             let params = DeepFriParams {
                 schedule: schedule.to_vec(),
                 r,
                 seed_z,
+                commitment_arity,
+                pow_bits,
             };
             let builder = DeepAliRealBuilder::default();
 
             eprintln!(
-                "mf-fri setup: label={} k={} (n0={}) schedule={:?} r={}",
-                label, k, n0, schedule, r
+                "mf-fri setup: label={} k={} (n0={}) schedule={:?} r={} pow_bits={}",
+                label, k, n0, schedule, r, pow_bits
             );
 
             // Precompute proof for verify bench and size
             eprintln!("mf-fri precompute proof…");
-            let pre_proof: DeepFriProof = deep_fri_prove(&builder, &a, &s, &e, &t, n0, &params);
+            let pre_proof: DeepFriProof = deep_fri_prove(&builder, &columns, n0, &params);
             let proof_size_bytes = deep_fri_proof_size_bytes(&pre_proof);
             eprintln!(
                 "mf-fri label={} k={} r={} proof≈{}B",
@@ -279,7 +597,7 @@ fn bench_e2e_mf_fri(c: &mut Criterion) {
                 b.iter_batched(
                     || (),
                     |_| {
-                        let proof = deep_fri_prove(&builder, &a, &s, &e, &t, n0, &params);
+                        let proof = deep_fri_prove(&builder, &columns, n0, &params);
                         criterion::black_box(proof);
                     },
                     BatchSize::SmallInput,
@@ -288,25 +606,50 @@ fn bench_e2e_mf_fri(c: &mut Criterion) {
 
             // Criterion bench: verify
             eprintln!("mf-fri precompute verify warmup…");
-            assert!(deep_fri_verify(&params, &pre_proof));
+            assert!(deep_fri_verify(&params, &pre_proof).is_ok());
             let verify_id = BenchmarkId::new(format!("verify-{}", label), k);
             g.bench_with_input(verify_id, &k, |b, &_k| {
                 b.iter(|| {
                     let ok = deep_fri_verify(&params, &pre_proof);
+                    assert!(ok.is_ok());
+                })
+            });
+
+            // Criterion bench: batch-verify (amortized query checks across several
+            // proofs sharing `params` -- see `deep_fri_verify_batch`).
+            const BATCH_SIZE: usize = 4;
+            let proof_batch: Vec<DeepFriProof> = (0..BATCH_SIZE)
+                .map(|_| deep_fri_prove(&builder, &columns, n0, &params))
+                .collect();
+            eprintln!("mf-fri precompute batch verify warmup…");
+            assert!(deep_fri_verify_batch(&params, &proof_batch));
+            let verify_batch_id = BenchmarkId::new(format!("verify-batch-{}", label), k);
+            g.bench_with_input(verify_batch_id, &k, |b, &_k| {
+                b.iter(|| {
+                    let ok = deep_fri_verify_batch(&params, &proof_batch);
                     assert!(ok);
                 })
             });
 
+            let t_batch0 = std::time::Instant::now();
+            assert!(deep_fri_verify_batch(&params, &proof_batch));
+            let batch_verify_ms = t_batch0.elapsed().as_secs_f64() * 1e3;
+            let amortized_verify_ms = batch_verify_ms / (BATCH_SIZE as f64);
+            eprintln!(
+                "mf-fri label={} k={} batch_size={} verify_batch_ms={:.3} amortized_verify_ms={:.3}",
+                label, k, BATCH_SIZE, batch_verify_ms, amortized_verify_ms
+            );
+
             // Single-shot timings to populate CSV
             // Prove
             let t0 = std::time::Instant::now();
-            let _tmp_proof = deep_fri_prove(&builder, &a, &s, &e, &t, n0, &params);
+            let _tmp_proof = deep_fri_prove(&builder, &columns, n0, &params);
             let prove_s = t0.elapsed().as_secs_f64();
 
             // Verify
             let t1 = std::time::Instant::now();
             let ok = deep_fri_verify(&params, &pre_proof);
-            assert!(ok);
+            assert!(ok.is_ok());
             let verify_ms = t1.elapsed().as_secs_f64() * 1e3;
 
             let prove_elems_per_s = (n0 as f64) / prove_s;
@@ -323,6 +666,12 @@ fn bench_e2e_mf_fri(c: &mut Criterion) {
                 delta_prove_pct: f64::NAN,
                 delta_verify_pct: f64::NAN,
                 delta_throughput_pct: f64::NAN,
+                cpu_model: hw.cpu_model.clone(),
+                cores: hw.cores,
+                mem_gb: hw.mem_gb,
+                field_mops: hw.field_mops,
+                mem_gbps: hw.mem_gbps,
+                machine_score: hw.machine_score,
             };
 
             // Compute deltas vs paper baseline for this k
@@ -341,6 +690,12 @@ fn bench_e2e_mf_fri(c: &mut Criterion) {
                         delta_prove_pct: 0.0,
                         delta_verify_pct: 0.0,
                         delta_throughput_pct: 0.0,
+                        cpu_model: row.cpu_model.clone(),
+                        cores: row.cores,
+                        mem_gb: row.mem_gb,
+                        field_mops: row.field_mops,
+                        mem_gbps: row.mem_gbps,
+                        machine_score: row.machine_score,
                     },
                 );
                 row.delta_size_pct = 0.0;
@@ -372,11 +727,76 @@ fn bench_e2e_mf_fri(c: &mut Criterion) {
                 .write_all(line.as_bytes())
                 .expect("failed to write CSV row");
             writer.flush().ok();
+
+            all_rows.push(row);
         }
     }
 
+    #[cfg(feature = "arrow_export")]
+    arrow_export::write_parquet("benchmarkdata.parquet", &all_rows);
+
+    g.finish();
+}
+
+// Contrasts the 255-bit Pallas `Fr` against the 64-bit `field::goldilocks::Goldilocks`
+// backend on the two operations `field::Fft2AdicField` makes generic: building a
+// 2^k-sized domain and running the per-layer even/odd fold over it. This is *not* an
+// apples-to-apples `deep_fri_prove`/`verify` comparison -- that pipeline's Merkle leaf
+// hash is Poseidon-over-Pallas specific and doesn't have a Goldilocks counterpart yet
+// (see the doc comment on `deep_ali::fri::FriDomain`) -- it isolates the part of the
+// win that's real today: a smaller field means cheaper field arithmetic per domain
+// point, independent of however the commitment layer eventually catches up.
+fn bench_fft2adic_domain(c: &mut Criterion) {
+    use field::goldilocks::Goldilocks;
+    use field::{fft2adic_fold_pair, fft2adic_subgroup, Fft2AdicField};
+
+    let mut g: BenchmarkGroup<WallTime> = c.benchmark_group("fft2adic_domain");
+    let k = 16usize;
+
+    g.bench_function("subgroup-pallas", |b| {
+        b.iter(|| fft2adic_subgroup::<F>(k));
+    });
+    g.bench_function("subgroup-goldilocks", |b| {
+        b.iter(|| fft2adic_subgroup::<Goldilocks>(k));
+    });
+
+    let dom_pallas = fft2adic_subgroup::<F>(k);
+    let beta_pallas = F::from(7u64);
+    g.bench_function("fold-pallas", |b| {
+        b.iter(|| {
+            let half = dom_pallas.len() / 2;
+            for i in 0..half {
+                std::hint::black_box(fft2adic_fold_pair(
+                    dom_pallas[i],
+                    dom_pallas[i + half],
+                    beta_pallas,
+                ));
+            }
+        });
+    });
+
+    let dom_gold = fft2adic_subgroup::<Goldilocks>(k);
+    let beta_gold = Goldilocks::new(7);
+    g.bench_function("fold-goldilocks", |b| {
+        b.iter(|| {
+            let half = dom_gold.len() / 2;
+            for i in 0..half {
+                std::hint::black_box(fft2adic_fold_pair(
+                    dom_gold[i],
+                    dom_gold[i + half],
+                    beta_gold,
+                ));
+            }
+        });
+    });
+
     g.finish();
 }
 
-criterion_group!(e2e, bench_e2e_plain, bench_e2e_mf_fri);
+criterion_group!(
+    e2e,
+    bench_e2e_plain,
+    bench_e2e_mf_fri,
+    bench_fft2adic_domain
+);
 criterion_main!(e2e);
\ No newline at end of file
@@ -7,7 +7,7 @@ use channel::{
     Mle, MleProver, MleVerifier, MerkleChannelCfg, MerkleProver, MerkleVerifier,
     SumCheckProver, SumCheckVerifier, SumCheckMFConfig, SumCheckMFProver, SumCheckMFVerifier,
 };
-use transcript::Transcript;
+use transcript::PoseidonTranscript;
 
 // -------- Size helpers --------
 
@@ -45,22 +45,10 @@ fn pk_size_bytes(pk: &PK) -> usize {
 
 // -------- Proof size helper (Merkle) --------
 
+// The real over-the-wire size: the canonical (ark-serialize) encoding `MerkleProof`
+// actually implements, not a hand-counted proxy that can drift from the struct.
 fn proof_size_bytes(proof: &commitment::MerkleProof) -> usize {
-    let mut total = 0usize;
-    total += 1; // arity
-    total += 8; // levels len (group_sizes)
-    for lvl in &proof.group_sizes {
-        total += 8;
-        total += lvl.len();
-    }
-    total += 8; // siblings levels len
-    for lvl in &proof.siblings {
-        total += 8;
-        for _s in lvl {
-            total += field_len_bytes();
-        }
-    }
-    total
+    proof.serialized_size()
 }
 
 // -------- Benches --------
@@ -95,8 +83,8 @@ fn bench_mle_commit_open(c: &mut Criterion) {
                 },
                 |(params, cfg, table, indices)| {
                     // Do the full protocol per iteration, owning all locals
-                    let p_tr = Transcript::new(b"PROTO-MLE", params.clone());
-                    let v_tr = Transcript::new(b"PROTO-MLE", params.clone());
+                    let p_tr = PoseidonTranscript::new(b"PROTO-MLE", params.clone());
+                    let v_tr = PoseidonTranscript::new(b"PROTO-MLE", params.clone());
                     let mut pchan = channel::ProverChannel::new(p_tr);
                     let mut vchan = channel::VerifierChannel::new(v_tr);
 
@@ -149,8 +137,8 @@ fn bench_sumcheck_plain(c: &mut Criterion) {
                 },
                 |(params, cfg, table, mle, k)| {
                     // Full protocol per iteration
-                    let p_tr = Transcript::new(b"SUMCHECK/PLAIN", params.clone());
-                    let v_tr = Transcript::new(b"SUMCHECK/PLAIN", params.clone());
+                    let p_tr = PoseidonTranscript::new(b"SUMCHECK/PLAIN", params.clone());
+                    let v_tr = PoseidonTranscript::new(b"SUMCHECK/PLAIN", params.clone());
                     let mut pchan = channel::ProverChannel::new(p_tr);
                     let mut vchan = channel::VerifierChannel::new(v_tr);
 
@@ -213,8 +201,8 @@ fn bench_sumcheck_mf(c: &mut Criterion) {
                 },
                 |(params, cfg, mle, mf_cfg)| {
                     // Full protocol per iteration
-                    let p_tr = Transcript::new(b"SUMCHECK/MF", params.clone());
-                    let v_tr = Transcript::new(b"SUMCHECK/MF", params.clone());
+                    let p_tr = PoseidonTranscript::new(b"SUMCHECK/MF", params.clone());
+                    let v_tr = PoseidonTranscript::new(b"SUMCHECK/MF", params.clone());
                     let mut pchan = channel::ProverChannel::new(p_tr);
                     let mut vchan = channel::VerifierChannel::new(v_tr);
 
@@ -240,8 +228,7 @@ fn bench_sumcheck_mf(c: &mut Criterion) {
 
                         sv.recv_next_root(next_root);
 
-                        total_proof_bytes += proof_size_bytes(&openings.cur_proof);
-                        total_proof_bytes += proof_size_bytes(&openings.next_proof);
+                        total_proof_bytes += openings.serialized_size();
 
                         assert!(sv.verify_fold_openings(
                             &openings.cur_indices,
@@ -1,13 +1,23 @@
-use ark_ff::{Field, One, Zero};
+use ark_ff::{Field, One, PrimeField, Zero};
 use ark_pallas::Fr as F;
-use ark_serialize::CanonicalSerialize;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
 use rand::{rngs::StdRng, Rng, SeedableRng};
 
 use ark_poly::domain::radix2::Radix2EvaluationDomain as Domain;
 use ark_poly::EvaluationDomain;
 
-use merkle::{MerkleChannelCfg, MerkleProof, MerkleProver, MerkleTree};
-use transcript::{default_params as transcript_params, Transcript};
+use field::Fft2AdicField;
+use merkle::{MerkleChannelCfg, MerkleProof, MerkleProver, MerkleTree, SiblingOrder};
+use transcript::{default_params as transcript_params, PoseidonTranscript};
+
+use crate::lagrange_eval_on_h;
+
+// Optional rayon-backed path for the hot per-bucket loops below (mirrors plonky2's
+// `maybe_rayon` shim): every index here is computed independently of every other, so
+// running them concurrently is bit-for-bit identical to the sequential version -- only
+// the iteration strategy changes. Single-threaded builds (the default) are unaffected.
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 #[cfg(feature = "fri_bench_log")]
 macro_rules! logln {
@@ -23,10 +33,21 @@ mod ds {
     pub const FRI_INDEX: &[u8] = b"FRI/index";
     pub const FRI_Z_L: &[u8] = b"FRI/z/l";
     pub const FRI_LEAF: &[u8] = b"FRI/leaf";
+    pub const BATCH_ALPHA: &[u8] = b"FRI/batch/alpha";
+    // Tags for `deep_fri_verify_batch`'s cross-proof aggregation, distinct from the
+    // per-proof `FRI_SEED`/`FRI_INDEX` tags above so a batch challenge can never be
+    // confused with (or replayed as) a single proof's own query-index seed.
+    pub const BATCH_VERIFY_COMBINE: &[u8] = b"FRI/batch-verify/combine";
+    pub const BATCH_VERIFY_RHO: &[u8] = b"FRI/batch-verify/rho";
+    pub const BATCH_VERIFY_CHECK_WEIGHT: &[u8] = b"FRI/batch-verify/check-weight";
+    // Folds the grinding nonce into the roots seed: `GRIND_NONCE` is distinct from
+    // `FRI_SEED`/`FRI_INDEX` so the grinding digest can never be replayed as (or
+    // confused with) either of those.
+    pub const GRIND_NONCE: &[u8] = b"FRI/grind/nonce";
 }
 
 fn tr_hash_fields_tagged(tag: &[u8], fields: &[F]) -> F {
-    let mut tr = Transcript::new(b"FRI/FS", transcript_params());
+    let mut tr = PoseidonTranscript::new(b"FRI/FS", transcript_params());
     tr.absorb_bytes(tag);
     for &x in fields {
         tr.absorb_field(x);
@@ -36,13 +57,27 @@ fn tr_hash_fields_tagged(tag: &[u8], fields: &[F]) -> F {
 
 // Poseidon hash of (f, s) to one field, domain-separated for leaves.
 fn hash_leaf_pair(f: F, s: F) -> F {
-    let mut tr = Transcript::new(b"FRI/leaf/poseidon", transcript_params());
+    let mut tr = PoseidonTranscript::new(b"FRI/leaf/poseidon", transcript_params());
     tr.absorb_bytes(ds::FRI_LEAF);
     tr.absorb_field(f);
     tr.absorb_field(s);
     tr.challenge(b"leaf")
 }
 
+/// The evaluation domain a DEEP-FRI layer runs over: a multiplicative subgroup of
+/// size `size = 2^k`, generated by `omega`.
+///
+/// Everything below this point (`FriDomain`, `fri_fold_layer`, `DeepFriParams`,
+/// `deep_fri_prove`/`deep_fri_verify`) is still instantiated at the single concrete
+/// field `F = ark_pallas::Fr`, not generic over [`field::Fft2AdicField`]: the Merkle
+/// leaf hash (`hash_leaf_pair`, and `merkle::PallasPoseidonHasher` underneath
+/// `MerkleTree` itself) is Poseidon-over-Pallas specific, so swapping the evaluation
+/// field alone wouldn't make the *pipeline* portable -- it would just make `FriDomain`
+/// hold values no Merkle leaf here knows how to hash. `new_two_adic` below builds
+/// `omega` via the new trait instead of `ark_poly`'s `Domain` to prove the two agree
+/// bit-for-bit and to give this struct a field-generic construction path that a future
+/// `MerkleHasher` for another backend (see that trait's doc comment in `merkle`) could
+/// build on without touching this struct again.
 #[derive(Clone, Copy, Debug)]
 pub struct FriDomain {
     pub omega: F,
@@ -54,6 +89,14 @@ impl FriDomain {
         let dom = Domain::<F>::new(size).expect("radix-2 domain exists");
         Self { omega: dom.group_gen, size }
     }
+
+    /// Same domain as `new_radix2`, constructed via `Fft2AdicField::root_of_unity`
+    /// instead of `ark_poly`'s `Radix2EvaluationDomain`. `size` must be a power of two.
+    pub fn new_two_adic(size: usize) -> Self {
+        let k = size.trailing_zeros() as usize;
+        assert_eq!(1usize << k, size, "FriDomain size must be a power of two");
+        Self { omega: <F as Fft2AdicField>::root_of_unity(k), size }
+    }
 }
 
 pub fn fri_sample_z_ell(seed_z: u64, level: usize, domain_size: usize) -> F {
@@ -85,19 +128,22 @@ pub fn fri_fold_layer(f_l: &[F], z_l: F, m: usize) -> Vec<F> {
     assert!(m >= 2);
     assert!(f_l.len() % m == 0, "layer size must be divisible by m");
     let n_next = f_l.len() / m;
-    let mut out = vec![F::zero(); n_next];
 
     let mut z_pows = Vec::with_capacity(m);
     let mut acc = F::one();
     for _ in 0..m { z_pows.push(acc); acc *= z_l; }
 
-    for b in 0..n_next {
+    let fold_bucket = |b: usize| -> F {
         let base = b * m;
         let mut s = F::zero();
         for t in 0..m { s += f_l[base + t] * z_pows[t]; }
-        out[b] = s;
-    }
-    out
+        s
+    };
+
+    #[cfg(feature = "parallel")]
+    { (0..n_next).into_par_iter().map(fold_bucket).collect() }
+    #[cfg(not(feature = "parallel"))]
+    { (0..n_next).map(fold_bucket).collect() }
 }
 
 pub fn fri_fold_schedule(f0: Vec<F>, schedule: &[usize], seed: u64) -> Vec<Vec<F>> {
@@ -128,17 +174,24 @@ pub fn compute_s_layer(f_l: &[F], z_l: F, m: usize) -> Vec<F> {
     let mut acc = F::one();
     for _ in 0..m { z_pows.push(acc); acc *= z_l; }
 
-    let mut s_bucket = vec![F::zero(); n_next];
-    for b in 0..n_next {
+    let fold_bucket = |b: usize| -> F {
         let base = b * m;
         let mut s = F::zero();
         for t in 0..m { s += f_l[base + t] * z_pows[t]; }
-        s_bucket[b] = s;
-    }
+        s
+    };
+
+    #[cfg(feature = "parallel")]
+    let s_bucket: Vec<F> = (0..n_next).into_par_iter().map(fold_bucket).collect();
+    #[cfg(not(feature = "parallel"))]
+    let s_bucket: Vec<F> = (0..n_next).map(fold_bucket).collect();
+
+    let expand_leaf = |i: usize| -> F { s_bucket[i / m] };
 
-    let mut s_per_i = vec![F::zero(); n];
-    for i in 0..n { s_per_i[i] = s_bucket[i / m]; }
-    s_per_i
+    #[cfg(feature = "parallel")]
+    { (0..n).into_par_iter().map(expand_leaf).collect() }
+    #[cfg(not(feature = "parallel"))]
+    { (0..n).map(expand_leaf).collect() }
 }
 
 fn layer_sizes_from_schedule(n0: usize, schedule: &[usize]) -> Vec<usize> {
@@ -176,6 +229,53 @@ fn verify_local_check_fold(
 
 fn fs_seed_from_roots(roots: &[F]) -> F { tr_hash_fields_tagged(ds::FRI_SEED, roots) }
 
+fn leading_zero_bits(x: &F) -> u32 {
+    x.into_bigint().to_bits_be().iter().take_while(|b| !**b).count() as u32
+}
+
+// Finds the smallest nonce (starting from 0) such that hashing it together with
+// `roots_seed` yields a digest with at least `pow_bits` leading zero bits -- the
+// proof-of-work grind `DeepFriParams::pow_bits` trades against `r` (see
+// `queries_for_security`). `pow_bits == 0` always returns `0` immediately, same as
+// `field::fri`'s `grind_nonce`.
+fn grind_nonce(roots_seed: F, pow_bits: u32) -> u64 {
+    if pow_bits == 0 {
+        return 0;
+    }
+    let mut nonce = 0u64;
+    loop {
+        let digest = tr_hash_fields_tagged(ds::GRIND_NONCE, &[roots_seed, F::from(nonce)]);
+        if leading_zero_bits(&digest) >= pow_bits {
+            return nonce;
+        }
+        nonce += 1;
+    }
+}
+
+// Folds the grinding nonce into the roots seed, producing the seed query-index
+// derivation actually runs from. Computed identically by prover and verifier: the
+// verifier never needs to re-run `grind_nonce`'s search, just this one hash, using the
+// nonce the proof already carries.
+fn grind_seed(roots_seed: F, nonce: u64) -> F {
+    tr_hash_fields_tagged(ds::GRIND_NONCE, &[roots_seed, F::from(nonce)])
+}
+
+/// The minimal number of FRI queries `r` that, combined with `pow_bits` grinding bits
+/// at the given Reed-Solomon `rate` (`rate = 1/blowup`, in `(0, 1)`), still hits
+/// `target_bits` of query soundness: each query contributes `log2(1/rate)` bits, each
+/// grinding bit contributes exactly one, so the smallest `r` satisfying
+/// `r * log2(1/rate) + pow_bits >= target_bits` is returned. Saturates at `0` when
+/// grinding alone already meets the target.
+pub fn queries_for_security(target_bits: u32, rate: f64, pow_bits: u32) -> usize {
+    assert!(rate > 0.0 && rate < 1.0, "rate must be in (0, 1)");
+    if pow_bits as f64 >= target_bits as f64 {
+        return 0;
+    }
+    let bits_per_query = (1.0 / rate).log2();
+    let remaining = target_bits as f64 - pow_bits as f64;
+    (remaining / bits_per_query).ceil() as usize
+}
+
 fn index_from_seed(seed_f: F, n_pow2: usize) -> usize {
     assert!(n_pow2.is_power_of_two());
     let mask = n_pow2 - 1;
@@ -204,7 +304,7 @@ pub struct FriLayerCommitment {
 #[derive(Clone)]
 pub struct FriTranscript { pub schedule: Vec<usize>, pub layers: Vec<FriLayerCommitment> }
 
-pub struct FriProverParams { pub schedule: Vec<usize>, pub seed_z: u64 }
+pub struct FriProverParams { pub schedule: Vec<usize>, pub seed_z: u64, pub commitment_arity: Option<usize> }
 
 pub struct FriProverState {
     pub f_layers: Vec<Vec<F>>,
@@ -258,38 +358,7 @@ pub fn fri_build_transcript(
     }
     s_layers.push(vec![F::zero(); f_layers[l].len()]);
 
-    let mut layers = Vec::with_capacity(l + 1);
-    for ell in 0..=l {
-        let n = f_layers[ell].len();
-        let m_ell = if ell < l { schedule[ell] } else { 1 };
-        let arity = pick_arity_for_layer(n, m_ell);
-        let use_hashed = arity == 16 || arity == 8;
-
-        let cfg = MerkleChannelCfg::new(arity).with_tree_label(ell as u64);
-        let prover = MerkleProver::new(cfg.clone());
-
-        let (root, tree) = if use_hashed {
-            // True single-column commit of h = Poseidon(f, s)
-            let mut h = Vec::with_capacity(n);
-            for i in 0..n { h.push(hash_leaf_pair(f_layers[ell][i], s_layers[ell][i])); }
-            let (root, tree) = prover.commit_single(&h[..]);
-            logln!("  committed layer {}: n={} m={} arity={} hashed=1(single)", ell, n, m_ell, arity);
-            (root, tree)
-        } else {
-            // For small arities, keep pair-commit of (f, s)
-            let (root, tree) = prover.commit_pairs(&f_layers[ell][..], &s_layers[ell][..]);
-            logln!("  committed layer {}: n={} m={} arity={} hashed=0(pairs)", ell, n, m_ell, arity);
-            (root, tree)
-        };
-
-        layers.push(FriLayerCommitment {
-            n, m: m_ell, root,
-            f: f_layers[ell].clone(),
-            s: s_layers[ell].clone(),
-            hashed_leaves: use_hashed,
-            tree, cfg,
-        });
-    }
+    let layers = commit_fri_layers(&f_layers, &s_layers, &schedule, params.commitment_arity);
 
     logln!("fri_build_transcript: done; last size={}", f_layers[l].len());
 
@@ -302,6 +371,68 @@ pub fn fri_build_transcript(
     }
 }
 
+// Shared by `fri_build_transcript` and `batch_fri_build_transcript`: commits each
+// layer's (f, s) pair vector (or their Poseidon(f, s) digest, for large arities) to
+// its own Merkle tree, given the already-folded layer values.
+fn commit_one_fri_layer(
+    ell: usize, n: usize, m_ell: usize, f_layer: &[F], s_layer: &[F], commitment_arity: Option<usize>,
+) -> FriLayerCommitment {
+    // `commitment_arity` overrides the auto-picked wide arity with a small, fixed one
+    // (2 or 4) so every layer's leaves hash natively over F with a short sibling
+    // path -- cheap for a future in-circuit recursive verifier to walk -- instead of
+    // auto-widening to whatever divides the layer size.
+    let arity = commitment_arity.unwrap_or_else(|| pick_arity_for_layer(n, m_ell));
+    let use_hashed = arity == 16 || arity == 8;
+
+    let cfg = MerkleChannelCfg::new(arity).with_tree_label(ell as u64).with_leaf_ds();
+    let prover = MerkleProver::new(cfg.clone());
+
+    let (root, tree) = if use_hashed {
+        // True single-column commit of h = Poseidon(f, s)
+        let hash_one = |i: usize| hash_leaf_pair(f_layer[i], s_layer[i]);
+        #[cfg(feature = "parallel")]
+        let h: Vec<F> = (0..n).into_par_iter().map(hash_one).collect();
+        #[cfg(not(feature = "parallel"))]
+        let h: Vec<F> = (0..n).map(hash_one).collect();
+        let (root, tree) = prover.commit_single(&h[..]);
+        logln!("  committed layer {}: n={} m={} arity={} hashed=1(single)", ell, n, m_ell, arity);
+        (root, tree)
+    } else {
+        // For small arities, keep pair-commit of (f, s)
+        let (root, tree) = prover.commit_pairs(f_layer, s_layer);
+        logln!("  committed layer {}: n={} m={} arity={} hashed=0(pairs)", ell, n, m_ell, arity);
+        (root, tree)
+    };
+
+    FriLayerCommitment {
+        n, m: m_ell, root,
+        f: f_layer.to_vec(),
+        s: s_layer.to_vec(),
+        hashed_leaves: use_hashed,
+        tree, cfg,
+    }
+}
+
+// Shared by `fri_build_transcript` and `batch_fri_build_transcript`: commits each
+// layer's (f, s) pair vector (or their Poseidon(f, s) digest, for large arities) to
+// its own Merkle tree, given the already-folded layer values. Layers are independent
+// of one another, so they're committed concurrently under the `parallel` feature.
+fn commit_fri_layers(
+    f_layers: &[Vec<F>], s_layers: &[Vec<F>], schedule: &[usize], commitment_arity: Option<usize>,
+) -> Vec<FriLayerCommitment> {
+    let l = schedule.len();
+    let commit_layer = |ell: usize| {
+        let n = f_layers[ell].len();
+        let m_ell = if ell < l { schedule[ell] } else { 1 };
+        commit_one_fri_layer(ell, n, m_ell, &f_layers[ell], &s_layers[ell], commitment_arity)
+    };
+
+    #[cfg(feature = "parallel")]
+    { (0..=l).into_par_iter().map(commit_layer).collect() }
+    #[cfg(not(feature = "parallel"))]
+    { (0..=l).map(commit_layer).collect() }
+}
+
 // Per-layer batched multiproofs and per-query references.
 #[derive(Clone)]
 pub struct LayerBatchProof {
@@ -463,8 +594,64 @@ pub type AliS = Vec<F>;
 pub type AliE = Vec<F>;
 pub type AliT = Vec<F>;
 
+// Combines many values into one via alpha-weighted powers: `reduce` folds a slice of
+// scalars left-to-right (`out = Σ alpha^(count+i) · xs[i]`), `reduce_polys` does the
+// same column-wise over several equal-length evaluation vectors. The running power
+// (`count`) advances after every call, so chained calls on the same instance continue
+// the same power sequence instead of restarting at `alpha^0` -- mirrors plonky2's
+// `util::reducing::ReducingFactor`.
+pub struct ReducingFactor {
+    alpha: F,
+    count: u64,
+}
+
+impl ReducingFactor {
+    pub fn new(alpha: F) -> Self {
+        Self { alpha, count: 0 }
+    }
+
+    fn alpha_pow(&self) -> F {
+        self.alpha.pow(&[self.count, 0, 0, 0])
+    }
+
+    pub fn reduce(&mut self, xs: &[F]) -> F {
+        let mut acc = F::zero();
+        let mut pow = self.alpha_pow();
+        for &x in xs {
+            acc += pow * x;
+            pow *= self.alpha;
+        }
+        self.count += xs.len() as u64;
+        acc
+    }
+
+    pub fn reduce_polys(&mut self, cols: &[Vec<F>]) -> Vec<F> {
+        if cols.is_empty() {
+            return Vec::new();
+        }
+        let n = cols[0].len();
+        assert!(cols.iter().all(|c| c.len() == n), "reduce_polys: all columns must share one length");
+
+        let mut pow = self.alpha_pow();
+        let mut out = vec![F::zero(); n];
+        for col in cols {
+            for i in 0..n { out[i] += pow * col[i]; }
+            pow *= self.alpha;
+        }
+        self.count += cols.len() as u64;
+        out
+    }
+}
+
+// `columns` are per-constraint evaluation vectors on H that each vanish there once the
+// witness is valid (the old fixed Φ = A·S + E − T is just `columns = vec![phi_eval]`,
+// a single column -- optional blinding is likewise just another column). They are
+// combined with one transcript-derived `alpha` (via `ReducingFactor`) into one merged
+// polynomial before the usual single `(x - z)` DEEP quotient, so callers with an
+// arbitrary number of constraint-quotient columns still feed exactly one stream into
+// FRI.
 pub trait DeepAliBuilder {
-    fn build_f0(&self, a: &AliA, s: &AliS, e: &AliE, t: &AliT, n0: usize, domain: FriDomain) -> Vec<F>;
+    fn build_f0(&self, columns: &[Vec<F>], n0: usize, domain: FriDomain) -> Vec<F>;
 }
 
 #[derive(Clone, Default)]
@@ -473,11 +660,10 @@ pub struct DeepAliMock;
 fn tr_hash_many(tag: &[u8], xs: &[F]) -> F { tr_hash_fields_tagged(tag, xs) }
 
 impl DeepAliBuilder for DeepAliMock {
-    fn build_f0(&self, a: &AliA, s: &AliS, e: &AliE, t: &AliT, n0: usize, _domain: FriDomain) -> Vec<F> {
-        let seed_f = tr_hash_fields_tagged(
-            b"ALI/mock/seed",
-            &[tr_hash_many(b"ALI/a", a), tr_hash_many(b"ALI/s", s), tr_hash_many(b"ALI/e", e), tr_hash_many(b"ALI/t", t), F::from(n0 as u64)],
-        );
+    fn build_f0(&self, columns: &[Vec<F>], n0: usize, _domain: FriDomain) -> Vec<F> {
+        let mut tags: Vec<F> = columns.iter().map(|c| tr_hash_many(b"ALI/col", c)).collect();
+        tags.push(F::from(n0 as u64));
+        let seed_f = tr_hash_fields_tagged(b"ALI/mock/seed", &tags);
         let mut seed_bytes = [0u8; 32];
         seed_f.serialize_uncompressed(&mut seed_bytes[..]).unwrap();
         let mut rng = StdRng::from_seed(seed_bytes);
@@ -485,16 +671,15 @@ impl DeepAliBuilder for DeepAliMock {
     }
 }
 
-// Real DEEP-ALI builder using lib.rs merge helpers
+// Real DEEP-ALI builder: randomly combines `columns` and applies the standard
+// single-point DEEP quotient against z.
 pub struct DeepAliRealBuilder {
-    pub r_eval_opt: Option<Vec<F>>, // optional blinding evaluations R on H
-    pub use_blinding: bool,
-    pub ds_tag: &'static [u8],       // domain-separation tag for (z, beta)
+    pub ds_tag: &'static [u8], // domain-separation tag for (z, alpha)
 }
 
 impl Default for DeepAliRealBuilder {
     fn default() -> Self {
-        Self { r_eval_opt: None, use_blinding: false, ds_tag: b"ALI/DEEP" }
+        Self { ds_tag: b"ALI/DEEP" }
     }
 }
 
@@ -524,38 +709,37 @@ fn ali_sample_z_beta_fs(tag: &[u8], n0: usize, roots_seed: F) -> (F, F) {
 }
 
 impl DeepAliBuilder for DeepAliRealBuilder {
-    fn build_f0(
-        &self,
-        a: &AliA, s: &AliS, e: &AliE, t: &AliT,
-        n0: usize, domain: FriDomain,
-    ) -> Vec<F> {
-        use crate::{deep_ali_merge_evals, deep_ali_merge_evals_blinded};
-        assert_eq!(a.len(), n0);
-        assert_eq!(s.len(), n0);
-        assert_eq!(e.len(), n0);
-        assert_eq!(t.len(), n0);
-
-        // FS-style seed from public ALI inputs
-        let seed_f = tr_hash_fields_tagged(
-            b"ALI/seed",
-            &[
-                tr_hash_fields_tagged(b"ALI/A", a),
-                tr_hash_fields_tagged(b"ALI/S", s),
-                tr_hash_fields_tagged(b"ALI/E", e),
-                tr_hash_fields_tagged(b"ALI/T", t),
-                F::from(n0 as u64),
-            ],
-        );
-
-        let (z, beta) = ali_sample_z_beta_fs(self.ds_tag, n0, seed_f);
-        let r_eval_opt_slice = self.r_eval_opt.as_ref().map(|v| &v[..]);
-
-        let (f0_eval, _z_out, _c_star) = if self.use_blinding {
-            deep_ali_merge_evals_blinded(a, s, e, t, r_eval_opt_slice, beta, domain.omega, z)
-        } else {
-            deep_ali_merge_evals(a, s, e, t, domain.omega, z)
-        };
+    fn build_f0(&self, columns: &[Vec<F>], n0: usize, domain: FriDomain) -> Vec<F> {
+        assert!(!columns.is_empty(), "build_f0: at least one quotient column required");
+        for col in columns {
+            assert_eq!(col.len(), n0);
+        }
 
+        // FS-style seed from the public columns, shared by both the (x - z) point and
+        // the random-linear-combination alpha.
+        let mut col_tags: Vec<F> = columns.iter().map(|c| tr_hash_fields_tagged(b"ALI/col", c)).collect();
+        let mut seed_inputs = col_tags.clone();
+        seed_inputs.push(F::from(n0 as u64));
+        let seed_f = tr_hash_fields_tagged(b"ALI/seed", &seed_inputs);
+
+        let (z, _beta) = ali_sample_z_beta_fs(self.ds_tag, n0, seed_f);
+        col_tags.push(F::from(col_tags.len() as u64));
+        let alpha = tr_hash_fields_tagged(b"ALI/alpha", &col_tags);
+
+        let phi_eval = ReducingFactor::new(alpha).reduce_polys(columns);
+
+        // Φ̃(z) via Lagrange, then the usual single-point DEEP quotient against z.
+        let phi_z = lagrange_eval_on_h(&phi_eval, z, domain.omega);
+        let zh_z = z.pow(&[n0 as u64, 0, 0, 0]) - F::one();
+        let c_star = phi_z * zh_z.inverse().expect("z outside H => Z_H(z) != 0");
+        let _ = c_star; // retained for parity with the non-generic merge; caller verifies via f0 only
+
+        let mut f0_eval = vec![F::zero(); n0];
+        let mut omega_j = F::one();
+        for j in 0..n0 {
+            f0_eval[j] = phi_eval[j] * (omega_j - z).inverse().expect("z outside H");
+            omega_j *= domain.omega;
+        }
         f0_eval
     }
 }
@@ -577,7 +761,23 @@ pub struct FriQueryPayload {
 }
 
 #[derive(Clone)]
-pub struct DeepFriParams { pub schedule: Vec<usize>, pub r: usize, pub seed_z: u64 }
+pub struct DeepFriParams {
+    pub schedule: Vec<usize>,
+    pub r: usize,
+    pub seed_z: u64,
+    // `None` keeps the existing auto-pick behavior (`pick_arity_for_layer`: widest
+    // arity the layer size divides evenly, capped at 16). `Some(2)`/`Some(4)` forces
+    // every layer's Merkle commitment to that arity instead, trading wider-tree
+    // prove/verify speed for authentication paths cheap enough for a future
+    // recursive (in-circuit) verifier to check.
+    pub commitment_arity: Option<usize>,
+    // Fiat-Shamir grinding difficulty: the prover must find a nonce making
+    // `grind_nonce`'s digest have at least this many leading zero bits before query
+    // indices are derived. Each bit here is worth exactly one query-soundness bit (see
+    // `queries_for_security`), so raising `pow_bits` lets `r` shrink for the same
+    // target security at the cost of one-time prover work. `0` disables grinding.
+    pub pow_bits: u32,
+}
 
 pub struct DeepFriProof {
     pub roots: Vec<F>,
@@ -587,25 +787,30 @@ pub struct DeepFriProof {
     pub queries: Vec<FriQueryPayload>,
     pub n0: usize,
     pub omega0: F,
+    // The grinding nonce `deep_fri_prove` found for `DeepFriParams::pow_bits`; `0`
+    // when grinding is disabled.
+    pub nonce: u64,
 }
 
 pub fn deep_fri_prove<B: DeepAliBuilder>(
-    builder: &B, a: &AliA, s: &AliS, e: &AliE, t: &AliT, n0: usize, params: &DeepFriParams,
+    builder: &B, columns: &[Vec<F>], n0: usize, params: &DeepFriParams,
 ) -> DeepFriProof {
     let domain0 = FriDomain::new_radix2(n0);
-    let f0 = builder.build_f0(a, s, e, t, n0, domain0);
+    let f0 = builder.build_f0(columns, n0, domain0);
 
     logln!("deep_fri_prove: building transcript");
     let st = fri_build_transcript(
         f0, domain0,
-        &FriProverParams { schedule: params.schedule.clone(), seed_z: params.seed_z },
+        &FriProverParams { schedule: params.schedule.clone(), seed_z: params.seed_z, commitment_arity: params.commitment_arity },
     );
 
     let roots: Vec<F> = st.transcript.layers.iter().map(|l| l.root).collect();
     let roots_seed = fs_seed_from_roots(&roots);
+    let nonce = grind_nonce(roots_seed, params.pow_bits);
+    let query_seed = grind_seed(roots_seed, nonce);
 
     // Build batched openings and per-query refs
-    let (refs_only, roots2, batches) = fri_prove_queries(&st, params.r, roots_seed);
+    let (refs_only, roots2, batches) = fri_prove_queries(&st, params.r, query_seed);
     debug_assert_eq!(roots, roots2);
 
     // Assemble per-query field payloads so the verifier can recompute hashed and pair leaves in batch order
@@ -628,14 +833,37 @@ pub fn deep_fri_prove<B: DeepAliBuilder>(
         });
     }
 
-    DeepFriProof { roots, layer_batches: batches, queries, n0, omega0: domain0.omega }
+    DeepFriProof { roots, layer_batches: batches, queries, n0, omega0: domain0.omega, nonce }
 }
 
-pub fn deep_fri_verify(params: &DeepFriParams, proof: &DeepFriProof) -> bool {
+// Pinpoints which check inside `deep_fri_verify` rejected a proof, rather than
+// collapsing prover bugs and adversarial mutations alike into a single `false`. `ell`
+// and `q` (where applicable) index the layer and query that failed, so a fuzzer can
+// assert on *which* invariant a mutated proof violated instead of just that it failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifyError {
+    ChildOpening { ell: usize },
+    ParentOpening { ell: usize },
+    FoldLocalCheck { ell: usize, q: usize, expected: F, got: F },
+    FinalIndexNonZero { q: usize },
+    FinalOpening,
+    MissingParentPair { ell: usize, index: usize },
+    GrindingInsufficient,
+}
+
+pub fn deep_fri_verify(params: &DeepFriParams, proof: &DeepFriProof) -> Result<(), VerifyError> {
     let L = params.schedule.len();
-    if proof.roots.len() != L + 1 { return false; }
-    if proof.layer_batches.layers.len() != L { return false; }
-    if proof.queries.len() != params.r { return false; }
+    if proof.roots.len() != L + 1 { return Err(VerifyError::FinalOpening); }
+    if proof.layer_batches.layers.len() != L { return Err(VerifyError::FinalOpening); }
+    if proof.queries.len() != params.r { return Err(VerifyError::FinalOpening); }
+
+    if params.pow_bits > 0 {
+        let roots_seed = fs_seed_from_roots(&proof.roots);
+        let digest = tr_hash_fields_tagged(ds::GRIND_NONCE, &[roots_seed, F::from(proof.nonce)]);
+        if leading_zero_bits(&digest) < params.pow_bits {
+            return Err(VerifyError::GrindingInsufficient);
+        }
+    }
 
     let sizes = layer_sizes_from_schedule(proof.n0, &params.schedule);
 
@@ -647,7 +875,7 @@ pub fn deep_fri_verify(params: &DeepFriParams, proof: &DeepFriProof) -> bool {
     for q in 0..params.r {
         let qp = &proof.queries[q];
         if qp.per_layer_refs.len() != L || qp.per_layer_payloads.len() != L {
-            return false;
+            return Err(VerifyError::FinalOpening);
         }
         for ell in 0..L {
             let rref = &qp.per_layer_refs[ell];
@@ -664,50 +892,50 @@ pub fn deep_fri_verify(params: &DeepFriParams, proof: &DeepFriProof) -> bool {
         // Child layer verification
         let ar_child = pick_arity_for_layer(sizes[ell], params.schedule[ell]);
         let hashed_child = ar_child == 16 || ar_child == 8;
-        let prover_child = MerkleProver::new(MerkleChannelCfg::new(ar_child).with_tree_label(ell as u64));
+        let prover_child = MerkleProver::new(MerkleChannelCfg::new(ar_child).with_tree_label(ell as u64).with_leaf_ds());
 
         if hashed_child {
             let mut leaves_h = Vec::with_capacity(lb.child_indices.len());
             for &i in &lb.child_indices {
-                let (f_i, s_i) = match child_maps[ell].get(&i) { Some(&p) => p, None => return false };
+                let (f_i, s_i) = match child_maps[ell].get(&i) { Some(&p) => p, None => return Err(VerifyError::ChildOpening { ell }) };
                 leaves_h.push(hash_leaf_pair(f_i, s_i));
             }
             if !prover_child.verify_single(&proof.roots[ell], &lb.child_indices, &leaves_h, &lb.child_proof) {
-                return false;
+                return Err(VerifyError::ChildOpening { ell });
             }
         } else {
             let mut pairs = Vec::with_capacity(lb.child_indices.len());
             for &i in &lb.child_indices {
-                let (f_i, s_i) = match child_maps[ell].get(&i) { Some(&p) => p, None => return false };
+                let (f_i, s_i) = match child_maps[ell].get(&i) { Some(&p) => p, None => return Err(VerifyError::ChildOpening { ell }) };
                 pairs.push((f_i, s_i));
             }
             if !prover_child.verify_pairs(&proof.roots[ell], &lb.child_indices, &pairs, &lb.child_proof) {
-                return false;
+                return Err(VerifyError::ChildOpening { ell });
             }
         }
 
         // Parent layer verification (against root at ell+1)
         let ar_parent = pick_arity_for_layer(sizes[ell + 1], if ell + 1 < L { params.schedule[ell + 1] } else { 1 });
         let hashed_parent = ar_parent == 16 || ar_parent == 8;
-        let prover_parent = MerkleProver::new(MerkleChannelCfg::new(ar_parent).with_tree_label((ell + 1) as u64));
+        let prover_parent = MerkleProver::new(MerkleChannelCfg::new(ar_parent).with_tree_label((ell + 1) as u64).with_leaf_ds());
 
         if hashed_parent {
             let mut leaves_parent_h = Vec::with_capacity(lb.parent_indices.len());
             for &b in &lb.parent_indices {
-                let (fpb, spb) = match parent_maps[ell].get(&b) { Some(&p) => p, None => return false };
+                let (fpb, spb) = match parent_maps[ell].get(&b) { Some(&p) => p, None => return Err(VerifyError::MissingParentPair { ell, index: b }) };
                 leaves_parent_h.push(hash_leaf_pair(fpb, spb));
             }
             if !prover_parent.verify_single(&proof.roots[ell + 1], &lb.parent_indices, &leaves_parent_h, &lb.parent_proof) {
-                return false;
+                return Err(VerifyError::ParentOpening { ell });
             }
         } else {
             let mut pairs_parent = Vec::with_capacity(lb.parent_indices.len());
             for &b in &lb.parent_indices {
-                let (fpb, spb) = match parent_maps[ell].get(&b) { Some(&p) => p, None => return false };
+                let (fpb, spb) = match parent_maps[ell].get(&b) { Some(&p) => p, None => return Err(VerifyError::MissingParentPair { ell, index: b }) };
                 pairs_parent.push((fpb, spb));
             }
             if !prover_parent.verify_pairs(&proof.roots[ell + 1], &lb.parent_indices, &pairs_parent, &lb.parent_proof) {
-                return false;
+                return Err(VerifyError::ParentOpening { ell });
             }
         }
     }
@@ -723,7 +951,7 @@ pub fn deep_fri_verify(params: &DeepFriParams, proof: &DeepFriProof) -> bool {
 
             let child_leaf_i = CombinedLeaf { f: pay.f_i, s: pay.s_i };
             if !verify_local_check_fold(rref.i, params.schedule[ell], n_layer, child_leaf_i, pay.f_parent_b) {
-                return false;
+                return Err(VerifyError::FoldLocalCheck { ell, q, expected: pay.f_parent_b, got: pay.s_i });
             }
         }
     }
@@ -733,64 +961,1808 @@ pub fn deep_fri_verify(params: &DeepFriParams, proof: &DeepFriProof) -> bool {
         let last_root = proof.roots[L];
         let ar_last = pick_arity_for_layer(sizes[L], 1);
         let hashed_last = ar_last == 16 || ar_last == 8;
-        let prover_last = MerkleProver::new(MerkleChannelCfg::new(ar_last).with_tree_label(L as u64));
+        let prover_last = MerkleProver::new(MerkleChannelCfg::new(ar_last).with_tree_label(L as u64).with_leaf_ds());
         let final_idx = proof.queries[0].final_index; // should be 0
-        if final_idx != 0 { return false; }
+        if final_idx != 0 { return Err(VerifyError::FinalIndexNonZero { q: 0 }); }
 
         if hashed_last {
             let leaf_h = hash_leaf_pair(proof.queries[0].final_pair.0, proof.queries[0].final_pair.1);
             if !prover_last.verify_single(&last_root, &[final_idx], &[leaf_h], &proof.layer_batches.final_proof) {
-                return false;
+                return Err(VerifyError::FinalOpening);
             }
         } else {
             if !prover_last.verify_pairs(&last_root, &[final_idx], &[proof.queries[0].final_pair], &proof.layer_batches.final_proof) {
-                return false;
+                return Err(VerifyError::FinalOpening);
             }
         }
     }
 
-    true
+    Ok(())
+}
+
+// A `DeepAliBuilder` that skips the ALI quotient construction entirely and hands
+// `fri_build_transcript` the caller's codeword unchanged. `DeepAliMock`/`DeepAliRealBuilder`
+// both exist to turn constraint columns into the one merged `f0_eval` FRI actually runs
+// on; this one is for callers (e.g. `deep_ali_merge_evals*`) who already hold that merged
+// codeword and just want the low-degree test run directly against it.
+pub struct IdentityColumnBuilder;
+
+impl DeepAliBuilder for IdentityColumnBuilder {
+    fn build_f0(&self, columns: &[Vec<F>], n0: usize, _domain: FriDomain) -> Vec<F> {
+        assert_eq!(columns.len(), 1, "IdentityColumnBuilder: exactly one precomputed codeword expected");
+        assert_eq!(columns[0].len(), n0, "IdentityColumnBuilder: codeword length must match n0");
+        columns[0].clone()
+    }
 }
 
-// ========== Proof size helpers (no-serde) ==========
+// Runs the folding low-degree test directly against `f0_eval`, an evaluation of the
+// claimed-low-degree polynomial over a coset of `domain`, with no ALI quotient step in
+// front of it. `DeepFriProof::roots` are the per-round layer commitments and
+// `DeepFriProof::queries` are the per-round authentication paths down to the final
+// layer; `params.schedule` of all-2 arities folds one round at a time, each round
+// combining adjacent entries as `f[2b] + z·f[2b+1]` (see `fri_fold_layer`), though any
+// schedule `deep_fri_prove` accepts works here too.
+pub fn prove_low_degree(domain: &FriDomain, f0_eval: &[F], params: &DeepFriParams) -> DeepFriProof {
+    deep_fri_prove(&IdentityColumnBuilder, &[f0_eval.to_vec()], domain.size, params)
+}
 
-const FR_BYTES: usize = 32;
-const INDEX_BYTES: usize = core::mem::size_of::<usize>();
+// Verifies a `prove_low_degree` proof: identical layer-to-layer folding and final-layer
+// opening checks to `deep_fri_verify`, just without an ALI quotient to re-derive.
+pub fn verify_low_degree(params: &DeepFriParams, proof: &DeepFriProof) -> bool {
+    deep_fri_verify(params, proof).is_ok()
+}
 
-fn merkle_proof_size_bytes(mp: &MerkleProof) -> usize {
-    let mut total = 0usize;
-    total += mp.siblings.iter().map(|grp| grp.len() * FR_BYTES).sum::<usize>();
-    total
+// ========== Batch verification of many DeepFriProofs sharing the same DeepFriParams ==========
+//
+// A forged Merkle authentication path isn't a field element, so there's no sound way
+// to fold that work across proofs committed to different roots -- each proof's tree
+// walks still happen in full, just concurrently (under the `parallel` feature) instead
+// of one-at-a-time, and so does the final-layer opening. What *does* collapse is the
+// cheap per-query arithmetic: the fold-consistency check (`s_i == f_parent[b]`), a
+// plain field equality. A combined Fiat-Shamir challenge over every proof's roots
+// binds the whole batch (so a prover can't cherry-pick which proofs to include after
+// seeing verifier randomness), then an FS-derived scalar per (proof, layer, query)
+// folds every one of those equalities into a single running sum that's compared to
+// zero exactly once, instead of as `proofs.len() * r * L` separate branches.
+
+fn deep_fri_batch_combined_seed(proofs: &[DeepFriProof]) -> F {
+    let all_roots: Vec<F> = proofs.iter().flat_map(|p| p.roots.iter().copied()).collect();
+    tr_hash_fields_tagged(ds::BATCH_VERIFY_COMBINE, &all_roots)
 }
 
-pub fn deep_fri_proof_size_bytes(p: &DeepFriProof) -> usize {
-    let mut total = 0usize;
+// Per-proof half of `deep_fri_verify_batch`: runs the exact same Merkle-authentication
+// and final-layer opening checks as `deep_fri_verify` (any failure there hard-fails the
+// whole batch), but instead of hard-failing on the first fold-consistency mismatch, folds
+// every one of those checks' `(expected - got)` residual into a running sum weighted
+// by an FS-derived, per-(layer, query) scalar `chi`. An honest proof's residual is
+// exactly zero; a single wrong opening makes the proof's (and hence the batch's)
+// residual nonzero with overwhelming probability.
+fn deep_fri_verify_residual(params: &DeepFriParams, proof: &DeepFriProof, combined_seed: F) -> Option<F> {
+    let l = params.schedule.len();
+    if proof.roots.len() != l + 1 { return None; }
+    if proof.layer_batches.layers.len() != l { return None; }
+    if proof.queries.len() != params.r { return None; }
+
+    let sizes = layer_sizes_from_schedule(proof.n0, &params.schedule);
+
+    use std::collections::BTreeMap;
+    let mut child_maps: Vec<BTreeMap<usize, (F, F)>> = vec![BTreeMap::new(); l];
+    let mut parent_maps: Vec<BTreeMap<usize, (F, F)>> = vec![BTreeMap::new(); l];
+
+    for q in 0..params.r {
+        let qp = &proof.queries[q];
+        if qp.per_layer_refs.len() != l || qp.per_layer_payloads.len() != l {
+            return None;
+        }
+        for ell in 0..l {
+            let rref = &qp.per_layer_refs[ell];
+            let pay = &qp.per_layer_payloads[ell];
+            child_maps[ell].entry(rref.i).or_insert((pay.f_i, pay.s_i));
+            parent_maps[ell].entry(rref.parent_index).or_insert((pay.f_parent_b, pay.s_parent_b));
+        }
+    }
+
+    for ell in 0..l {
+        let lb = &proof.layer_batches.layers[ell];
+
+        let ar_child = pick_arity_for_layer(sizes[ell], params.schedule[ell]);
+        let hashed_child = ar_child == 16 || ar_child == 8;
+        let prover_child = MerkleProver::new(MerkleChannelCfg::new(ar_child).with_tree_label(ell as u64).with_leaf_ds());
+
+        if hashed_child {
+            let mut leaves_h = Vec::with_capacity(lb.child_indices.len());
+            for &i in &lb.child_indices {
+                let (f_i, s_i) = child_maps[ell].get(&i).copied()?;
+                leaves_h.push(hash_leaf_pair(f_i, s_i));
+            }
+            if !prover_child.verify_single(&proof.roots[ell], &lb.child_indices, &leaves_h, &lb.child_proof) {
+                return None;
+            }
+        } else {
+            let mut pairs = Vec::with_capacity(lb.child_indices.len());
+            for &i in &lb.child_indices {
+                pairs.push(child_maps[ell].get(&i).copied()?);
+            }
+            if !prover_child.verify_pairs(&proof.roots[ell], &lb.child_indices, &pairs, &lb.child_proof) {
+                return None;
+            }
+        }
+
+        let ar_parent = pick_arity_for_layer(sizes[ell + 1], if ell + 1 < l { params.schedule[ell + 1] } else { 1 });
+        let hashed_parent = ar_parent == 16 || ar_parent == 8;
+        let prover_parent = MerkleProver::new(MerkleChannelCfg::new(ar_parent).with_tree_label((ell + 1) as u64).with_leaf_ds());
+
+        if hashed_parent {
+            let mut leaves_parent_h = Vec::with_capacity(lb.parent_indices.len());
+            for &b in &lb.parent_indices {
+                let (fpb, spb) = parent_maps[ell].get(&b).copied()?;
+                leaves_parent_h.push(hash_leaf_pair(fpb, spb));
+            }
+            if !prover_parent.verify_single(&proof.roots[ell + 1], &lb.parent_indices, &leaves_parent_h, &lb.parent_proof) {
+                return None;
+            }
+        } else {
+            let mut pairs_parent = Vec::with_capacity(lb.parent_indices.len());
+            for &b in &lb.parent_indices {
+                pairs_parent.push(parent_maps[ell].get(&b).copied()?);
+            }
+            if !prover_parent.verify_pairs(&proof.roots[ell + 1], &lb.parent_indices, &pairs_parent, &lb.parent_proof) {
+                return None;
+            }
+        }
+    }
+
+    let mut acc = F::zero();
+    let layer_domains = layer_domains_from_schedule(proof.n0, &params.schedule);
+    for q in 0..params.r {
+        let qp = &proof.queries[q];
+        for ell in 0..l {
+            let rref = &qp.per_layer_refs[ell];
+            let pay = &qp.per_layer_payloads[ell];
+            let (n_layer, _omega_l) = layer_domains[ell];
+            let m = params.schedule[ell];
+            let b = rref.i / m;
+            if b >= n_layer / m {
+                return None;
+            }
+            let chi = tr_hash_fields_tagged(
+                ds::BATCH_VERIFY_CHECK_WEIGHT,
+                &[combined_seed, F::from(ell as u64), F::from(q as u64)],
+            );
+            acc += chi * (pay.s_i - pay.f_parent_b);
+        }
+    }
+
+    {
+        let last_root = proof.roots[l];
+        let ar_last = pick_arity_for_layer(sizes[l], 1);
+        let hashed_last = ar_last == 16 || ar_last == 8;
+        let prover_last = MerkleProver::new(MerkleChannelCfg::new(ar_last).with_tree_label(l as u64).with_leaf_ds());
+        let final_idx = proof.queries[0].final_index;
+        if final_idx != 0 { return None; }
+
+        let ok = if hashed_last {
+            let leaf_h = hash_leaf_pair(proof.queries[0].final_pair.0, proof.queries[0].final_pair.1);
+            prover_last.verify_single(&last_root, &[final_idx], &[leaf_h], &proof.layer_batches.final_proof)
+        } else {
+            prover_last.verify_pairs(&last_root, &[final_idx], &[proof.queries[0].final_pair], &proof.layer_batches.final_proof)
+        };
+        if !ok { return None; }
+    }
+
+    Some(acc)
+}
+
+/// Verify many `DeepFriProof`s that share the same `DeepFriParams` together. See the
+/// module-level note above this function for what is and isn't actually amortized.
+pub fn deep_fri_verify_batch(params: &DeepFriParams, proofs: &[DeepFriProof]) -> bool {
+    if proofs.is_empty() {
+        return false;
+    }
+
+    let combined_seed = deep_fri_batch_combined_seed(proofs);
+    let rhos: Vec<F> = (0..proofs.len())
+        .map(|i| tr_hash_fields_tagged(ds::BATCH_VERIFY_RHO, &[combined_seed, F::from(i as u64)]))
+        .collect();
+
+    #[cfg(feature = "parallel")]
+    let residuals: Vec<Option<F>> = proofs
+        .par_iter()
+        .map(|p| deep_fri_verify_residual(params, p, combined_seed))
+        .collect();
+    #[cfg(not(feature = "parallel"))]
+    let residuals: Vec<Option<F>> = proofs
+        .iter()
+        .map(|p| deep_fri_verify_residual(params, p, combined_seed))
+        .collect();
+
+    let mut acc = F::zero();
+    for (residual, &rho) in residuals.iter().zip(rhos.iter()) {
+        match residual {
+            Some(r) => acc += rho * r,
+            None => return false,
+        }
+    }
+
+    acc.is_zero()
+}
+
+/// Prover-side counterpart: proves each column-set against the same `DeepFriParams`
+/// and returns the resulting proofs in order, ready to hand to `deep_fri_verify_batch`.
+/// Each proof is still produced independently (there's no shared commitment to
+/// aggregate on the prove side, only on verify), so this is mostly a convenience
+/// wrapper -- its main job is keeping callers from having to re-derive
+/// `deep_fri_batch_combined_seed` by hand if they want to sanity-check a batch (e.g.
+/// before shipping it over the network) the same way the verifier will.
+pub fn deep_fri_prove_many<B: DeepAliBuilder>(
+    builder: &B, columns_per_proof: &[Vec<Vec<F>>], n0: usize, params: &DeepFriParams,
+) -> Vec<DeepFriProof> {
+    columns_per_proof
+        .iter()
+        .map(|columns| deep_fri_prove(builder, columns, n0, params))
+        .collect()
+}
+
+impl DeepFriProof {
+    // Recomputes every layer root from the opened leaves and sibling data embedded in
+    // this proof, instead of checking a transmitted root against the transcript. This
+    // lets a caller authenticate the whole proof by embedding only a signature over
+    // `recover_layer_roots()`'s output (or comparing it to `self.roots` in one pass, as
+    // done here): each recovered root is compared against `self.roots[ell]` as soon as
+    // it's available, short-circuiting on the first mismatch rather than reconstructing
+    // every layer before discovering the proof is bad.
+    pub fn recover_layer_roots(&self, params: &DeepFriParams) -> Option<Vec<F>> {
+        let L = params.schedule.len();
+        if self.roots.len() != L + 1 { return None; }
+        if self.layer_batches.layers.len() != L { return None; }
+        if self.queries.len() != params.r { return None; }
+
+        let sizes = layer_sizes_from_schedule(self.n0, &params.schedule);
+
+        use std::collections::BTreeMap;
+        let mut child_maps: Vec<BTreeMap<usize, (F, F)>> = vec![BTreeMap::new(); L];
+        let mut parent_maps: Vec<BTreeMap<usize, (F, F)>> = vec![BTreeMap::new(); L];
+
+        for q in 0..params.r {
+            let qp = &self.queries[q];
+            if qp.per_layer_refs.len() != L || qp.per_layer_payloads.len() != L {
+                return None;
+            }
+            for ell in 0..L {
+                let rref = &qp.per_layer_refs[ell];
+                let pay = &qp.per_layer_payloads[ell];
+                child_maps[ell].entry(rref.i).or_insert((pay.f_i, pay.s_i));
+                parent_maps[ell].entry(rref.parent_index).or_insert((pay.f_parent_b, pay.s_parent_b));
+            }
+        }
+
+        let mut recovered = vec![None; L + 1];
+
+        for ell in 0..L {
+            let lb = &self.layer_batches.layers[ell];
+
+            let ar_child = pick_arity_for_layer(sizes[ell], params.schedule[ell]);
+            let hashed_child = ar_child == 16 || ar_child == 8;
+            let prover_child = MerkleProver::new(MerkleChannelCfg::new(ar_child).with_tree_label(ell as u64).with_leaf_ds());
+
+            let root_child = if hashed_child {
+                let mut leaves_h = Vec::with_capacity(lb.child_indices.len());
+                for &i in &lb.child_indices {
+                    let (f_i, s_i) = match child_maps[ell].get(&i) { Some(&p) => p, None => return None };
+                    leaves_h.push(hash_leaf_pair(f_i, s_i));
+                }
+                prover_child.recover_root(&lb.child_indices, &leaves_h, &lb.child_proof)?
+            } else {
+                let mut pairs = Vec::with_capacity(lb.child_indices.len());
+                for &i in &lb.child_indices {
+                    let (f_i, s_i) = match child_maps[ell].get(&i) { Some(&p) => p, None => return None };
+                    pairs.push((f_i, s_i));
+                }
+                prover_child.recover_root_pairs(&lb.child_indices, &pairs, &lb.child_proof)?
+            };
+            if root_child != self.roots[ell] { return None; }
+            recovered[ell] = Some(root_child);
+
+            let ar_parent = pick_arity_for_layer(sizes[ell + 1], if ell + 1 < L { params.schedule[ell + 1] } else { 1 });
+            let hashed_parent = ar_parent == 16 || ar_parent == 8;
+            let prover_parent = MerkleProver::new(MerkleChannelCfg::new(ar_parent).with_tree_label((ell + 1) as u64).with_leaf_ds());
+
+            let root_parent = if hashed_parent {
+                let mut leaves_parent_h = Vec::with_capacity(lb.parent_indices.len());
+                for &b in &lb.parent_indices {
+                    let (fpb, spb) = match parent_maps[ell].get(&b) { Some(&p) => p, None => return None };
+                    leaves_parent_h.push(hash_leaf_pair(fpb, spb));
+                }
+                prover_parent.recover_root(&lb.parent_indices, &leaves_parent_h, &lb.parent_proof)?
+            } else {
+                let mut pairs_parent = Vec::with_capacity(lb.parent_indices.len());
+                for &b in &lb.parent_indices {
+                    let (fpb, spb) = match parent_maps[ell].get(&b) { Some(&p) => p, None => return None };
+                    pairs_parent.push((fpb, spb));
+                }
+                prover_parent.recover_root_pairs(&lb.parent_indices, &pairs_parent, &lb.parent_proof)?
+            };
+            if root_parent != self.roots[ell + 1] { return None; }
+            recovered[ell + 1] = Some(root_parent);
+        }
+
+        {
+            let sizes_last = sizes[L];
+            let ar_last = pick_arity_for_layer(sizes_last, 1);
+            let hashed_last = ar_last == 16 || ar_last == 8;
+            let prover_last = MerkleProver::new(MerkleChannelCfg::new(ar_last).with_tree_label(L as u64).with_leaf_ds());
+            let final_idx = self.queries[0].final_index;
+            if final_idx != 0 { return None; }
+
+            let root_last = if hashed_last {
+                let leaf_h = hash_leaf_pair(self.queries[0].final_pair.0, self.queries[0].final_pair.1);
+                prover_last.recover_root(&[final_idx], &[leaf_h], &self.layer_batches.final_proof)?
+            } else {
+                prover_last.recover_root_pairs(&[final_idx], &[self.queries[0].final_pair], &self.layer_batches.final_proof)?
+            };
+            if root_last != self.roots[L] { return None; }
+            recovered[L] = Some(root_last);
+        }
+
+        recovered.into_iter().collect()
+    }
+}
+
+// ========== Batch FRI: several codewords of different sizes, one transcript ==========
+//
+// `fri_build_transcript`/`deep_fri_prove` fold a single `f0`. This mirrors plonky2's
+// `batch_fri`: fold one running codeword starting from the largest polynomial, and
+// whenever the running layer's size matches a smaller, not-yet-mixed polynomial's
+// size, absorb it in via one Fiat-Shamir `alpha` (a reducing factor: `running[i] =
+// running[i] * alpha + P_j[i]`). Every P_j after the first is precommitted to its own
+// single-column tree -- not folded itself -- purely so its leaf can be authenticated
+// at the query index where it gets mixed in.
+
+const BATCH_POLY_TREE_LABEL_BASE: u64 = 1_000_000;
+
+#[derive(Clone)]
+pub struct BatchFriInput {
+    pub size: usize,
+    pub root: F,
+    pub tree: MerkleTree,
+    pub cfg: MerkleChannelCfg,
+    pub evals: Vec<F>,
+}
+
+pub struct BatchFriProverState {
+    pub inner: FriProverState,
+    pub alpha: F,
+    pub mixed_polys: Vec<BatchFriInput>,
+}
+
+// Folds `polys[0]` (the largest, matching `domain0`) exactly like `fri_build_transcript`,
+// mixing in `polys[1..]` (each strictly smaller, in decreasing size order) whenever the
+// running codeword's size matches theirs.
+pub fn batch_fri_build_transcript(
+    polys: &[Vec<F>], domain0: FriDomain, params: &FriProverParams,
+) -> BatchFriProverState {
+    assert!(!polys.is_empty(), "batch_fri_build_transcript: no polynomials");
+    for w in polys.windows(2) {
+        assert!(w[0].len() > w[1].len(), "batch_fri_build_transcript: polys must be strictly decreasing in size");
+    }
+    assert_eq!(polys[0].len(), domain0.size, "batch_fri_build_transcript: largest poly must match domain0.size");
+
+    let schedule = params.schedule.clone();
+    let layer_sizes = layer_sizes_from_schedule(domain0.size, &schedule);
+    for p in &polys[1..] {
+        assert!(
+            layer_sizes.contains(&p.len()),
+            "batch_fri_build_transcript: polynomial of size {} never appears as a fold layer",
+            p.len()
+        );
+    }
+
+    // Precommit every polynomial after the first so its leaf can be opened later at
+    // the fold step where it gets mixed in.
+    let mixed_polys: Vec<BatchFriInput> = polys[1..]
+        .iter()
+        .enumerate()
+        .map(|(j, p)| {
+            let arity = pick_arity_for_layer(p.len(), 2);
+            let cfg = MerkleChannelCfg::new(arity).with_tree_label(BATCH_POLY_TREE_LABEL_BASE + j as u64).with_leaf_ds();
+            let prover = MerkleProver::new(cfg.clone());
+            let (root, tree) = prover.commit_single(p);
+            BatchFriInput { size: p.len(), root, tree, cfg, evals: p.clone() }
+        })
+        .collect();
+
+    let alpha = if mixed_polys.is_empty() {
+        F::one()
+    } else {
+        let roots: Vec<F> = mixed_polys.iter().map(|b| b.root).collect();
+        tr_hash_fields_tagged(ds::BATCH_ALPHA, &roots)
+    };
 
-    // Roots per layer
-    total += p.roots.len() * FR_BYTES;
+    let mix_into = |cur: &mut [F], size: usize| {
+        for p in &polys[1..] {
+            if p.len() == size {
+                for i in 0..size { cur[i] = cur[i] * alpha + p[i]; }
+            }
+        }
+    };
+
+    let l = schedule.len();
+    let layer_domains = layer_domains_from_schedule(domain0.size, &schedule);
+
+    let mut f_layers = Vec::with_capacity(l + 1);
+    let mut z_layers = Vec::with_capacity(l);
+    let mut omega_layers = Vec::with_capacity(l);
+    let mut cur_f = polys[0].clone();
+    let mut cur_size = domain0.size;
+    f_layers.push(cur_f.clone());
 
-    // Params carried alongside proof (if you serialize them)
-    total += FR_BYTES; // omega0
-    total += INDEX_BYTES; // n0
+    for (ell, &m) in schedule.iter().enumerate() {
+        let z = fri_sample_z_ell(params.seed_z, ell, cur_size);
+        z_layers.push(z);
+        let (_n_ell, omega_ell) = layer_domains[ell];
+        omega_layers.push(omega_ell);
+        cur_f = fri_fold_layer(&cur_f, z, m);
+        cur_size /= m;
+        mix_into(&mut cur_f, cur_size);
+        f_layers.push(cur_f.clone());
+    }
 
-    // Batched proofs per layer: child + parent
-    for lb in &p.layer_batches.layers {
-        total += merkle_proof_size_bytes(&lb.child_proof);
-        total += merkle_proof_size_bytes(&lb.parent_proof);
-        // Plus indices arrays (if serialized)
-        total += lb.child_indices.len() * INDEX_BYTES;
-        total += lb.parent_indices.len() * INDEX_BYTES;
+    let mut s_layers = Vec::with_capacity(l + 1);
+    for ell in 0..l {
+        let m = schedule[ell];
+        let z = z_layers[ell];
+        s_layers.push(compute_s_layer(&f_layers[ell], z, m));
     }
-    total += merkle_proof_size_bytes(&p.layer_batches.final_proof);
+    s_layers.push(vec![F::zero(); f_layers[l].len()]);
 
-    // Per-query small payloads
-    for q in &p.queries {
-        total += INDEX_BYTES; // final_index
-        total += 2 * FR_BYTES; // final_pair
-        // Per-layer refs and payloads
-        total += q.per_layer_refs.len() * (2 * INDEX_BYTES); // child_pos + parent_pos
-        total += q.per_layer_payloads.len() * (4 * FR_BYTES); // f_i, s_i, f_parent_b, s_parent_b
+    let layers = commit_fri_layers(&f_layers, &s_layers, &schedule, params.commitment_arity);
+
+    BatchFriProverState {
+        inner: FriProverState {
+            f_layers,
+            s_layers,
+            transcript: FriTranscript { schedule, layers },
+            omega_layers,
+            z_layers,
+        },
+        alpha,
+        mixed_polys,
     }
+}
+
+// A union-of-paths opening of one mixed polynomial at the query indices that fall on
+// the fold layer where it gets absorbed.
+#[derive(Clone)]
+pub struct MixedPolyOpening {
+    pub size: usize,
+    pub indices: Vec<usize>,
+    pub values: Vec<F>,
+    pub proof: MerkleProof,
+}
+
+pub struct BatchFriProof {
+    pub roots: Vec<F>,
+    pub mixed_poly_roots: Vec<F>,
+    pub layer_batches: FriLayerBatches,
+    pub queries: Vec<FriQueryPayload>,
+    pub mixed_openings: Vec<MixedPolyOpening>,
+    pub n0: usize,
+    pub omega0: F,
+    pub alpha: F,
+}
 
-    total
+pub fn batch_fri_prove(
+    polys: &[Vec<F>], n0: usize, params: &DeepFriParams,
+) -> BatchFriProof {
+    let domain0 = FriDomain::new_radix2(n0);
+    let st = batch_fri_build_transcript(
+        polys, domain0,
+        &FriProverParams { schedule: params.schedule.clone(), seed_z: params.seed_z, commitment_arity: params.commitment_arity },
+    );
+
+    let roots: Vec<F> = st.inner.transcript.layers.iter().map(|l| l.root).collect();
+    let roots_seed = fs_seed_from_roots(&roots);
+
+    let (refs_only, roots2, batches) = fri_prove_queries(&st.inner, params.r, roots_seed);
+    debug_assert_eq!(roots, roots2);
+
+    let mut queries: Vec<FriQueryPayload> = Vec::with_capacity(params.r);
+    for q in 0..params.r {
+        let mut per_layer_payloads = Vec::with_capacity(params.schedule.len());
+        for ell in 0..params.schedule.len() {
+            let rref = &refs_only[q].per_layer_refs[ell];
+            let f_i = st.inner.transcript.layers[ell].f[rref.i];
+            let s_i = st.inner.transcript.layers[ell].s[rref.i];
+            let f_parent_b = st.inner.transcript.layers[ell + 1].f[rref.parent_index];
+            let s_parent_b = st.inner.transcript.layers[ell + 1].s[rref.parent_index];
+            per_layer_payloads.push(LayerOpenPayload { f_i, s_i, f_parent_b, s_parent_b });
+        }
+        queries.push(FriQueryPayload {
+            per_layer_refs: refs_only[q].per_layer_refs.clone(),
+            per_layer_payloads,
+            final_index: refs_only[q].final_index,
+            final_pair: refs_only[q].final_pair,
+        });
+    }
+
+    // Each mixed polynomial is opened once, at every parent index its matching fold
+    // layer's batch already needed -- no extra query-index derivation required.
+    let layer_sizes = layer_sizes_from_schedule(n0, &params.schedule);
+    let mixed_openings: Vec<MixedPolyOpening> = st
+        .mixed_polys
+        .iter()
+        .map(|bp| {
+            let layer_idx = layer_sizes
+                .iter()
+                .position(|&s| s == bp.size)
+                .expect("mixed poly size must match a fold layer");
+            let indices = batches.layers[layer_idx - 1].parent_indices.clone();
+            let proof = bp.tree.open_many_single(&indices);
+            let values: Vec<F> = indices.iter().map(|&i| bp.evals[i]).collect();
+            MixedPolyOpening { size: bp.size, indices, values, proof }
+        })
+        .collect();
+
+    BatchFriProof {
+        roots,
+        mixed_poly_roots: st.mixed_polys.iter().map(|b| b.root).collect(),
+        layer_batches: batches,
+        queries,
+        mixed_openings,
+        n0,
+        omega0: domain0.omega,
+        alpha: st.alpha,
+    }
+}
+
+// Same fold-consistency check as `verify_local_check_fold`, but when `mixed_leaf` is
+// present the parent value is expected to be `s_i * alpha + mixed_leaf` instead of
+// bare `s_i` (the layer was mixed with a smaller polynomial before being committed).
+fn verify_local_check_fold_batched(
+    child_leaf_i: CombinedLeaf, parent_f_b: F, mixed_leaf: Option<F>, alpha: F,
+) -> bool {
+    match mixed_leaf {
+        Some(p_val) => child_leaf_i.s * alpha + p_val == parent_f_b,
+        None => child_leaf_i.s == parent_f_b,
+    }
+}
+
+pub fn batch_fri_verify(params: &DeepFriParams, proof: &BatchFriProof) -> bool {
+    let L = params.schedule.len();
+    if proof.roots.len() != L + 1 { return false; }
+    if proof.layer_batches.layers.len() != L { return false; }
+    if proof.queries.len() != params.r { return false; }
+    if proof.mixed_openings.len() != proof.mixed_poly_roots.len() { return false; }
+
+    let sizes = layer_sizes_from_schedule(proof.n0, &params.schedule);
+
+    // Verify each mixed polynomial's own opening against its own root, and index the
+    // opened values by (layer, index) so the local fold check can look them up.
+    use std::collections::BTreeMap;
+    let mut mixed_by_layer: BTreeMap<usize, BTreeMap<usize, F>> = BTreeMap::new();
+    for (j, (mo, &root)) in proof.mixed_openings.iter().zip(proof.mixed_poly_roots.iter()).enumerate() {
+        let layer_idx = match sizes.iter().position(|&s| s == mo.size) {
+            Some(idx) => idx,
+            None => return false,
+        };
+        if mo.indices.len() != mo.values.len() { return false; }
+        let arity = pick_arity_for_layer(mo.size, 2);
+        let cfg = MerkleChannelCfg::new(arity).with_tree_label(BATCH_POLY_TREE_LABEL_BASE + j as u64).with_leaf_ds();
+        let prover = MerkleProver::new(cfg);
+        if !prover.verify_single(&root, &mo.indices, &mo.values, &mo.proof) {
+            return false;
+        }
+        let mut map = BTreeMap::new();
+        for (&i, &v) in mo.indices.iter().zip(mo.values.iter()) { map.insert(i, v); }
+        mixed_by_layer.insert(layer_idx, map);
+    }
+
+    // Prepare per-layer maps: index -> (f,s) and parent index -> (f,s), same shape as
+    // the single-poly verifier.
+    let mut child_maps: Vec<BTreeMap<usize, (F, F)>> = vec![BTreeMap::new(); L];
+    let mut parent_maps: Vec<BTreeMap<usize, (F, F)>> = vec![BTreeMap::new(); L];
+
+    for q in 0..params.r {
+        let qp = &proof.queries[q];
+        if qp.per_layer_refs.len() != L || qp.per_layer_payloads.len() != L {
+            return false;
+        }
+        for ell in 0..L {
+            let rref = &qp.per_layer_refs[ell];
+            let pay = &qp.per_layer_payloads[ell];
+            child_maps[ell].entry(rref.i).or_insert((pay.f_i, pay.s_i));
+            parent_maps[ell].entry(rref.parent_index).or_insert((pay.f_parent_b, pay.s_parent_b));
+        }
+    }
+
+    for ell in 0..L {
+        let lb = &proof.layer_batches.layers[ell];
+
+        let ar_child = pick_arity_for_layer(sizes[ell], params.schedule[ell]);
+        let hashed_child = ar_child == 16 || ar_child == 8;
+        let prover_child = MerkleProver::new(MerkleChannelCfg::new(ar_child).with_tree_label(ell as u64).with_leaf_ds());
+
+        if hashed_child {
+            let mut leaves_h = Vec::with_capacity(lb.child_indices.len());
+            for &i in &lb.child_indices {
+                let (f_i, s_i) = match child_maps[ell].get(&i) { Some(&p) => p, None => return false };
+                leaves_h.push(hash_leaf_pair(f_i, s_i));
+            }
+            if !prover_child.verify_single(&proof.roots[ell], &lb.child_indices, &leaves_h, &lb.child_proof) {
+                return false;
+            }
+        } else {
+            let mut pairs = Vec::with_capacity(lb.child_indices.len());
+            for &i in &lb.child_indices {
+                let (f_i, s_i) = match child_maps[ell].get(&i) { Some(&p) => p, None => return false };
+                pairs.push((f_i, s_i));
+            }
+            if !prover_child.verify_pairs(&proof.roots[ell], &lb.child_indices, &pairs, &lb.child_proof) {
+                return false;
+            }
+        }
+
+        let ar_parent = pick_arity_for_layer(sizes[ell + 1], if ell + 1 < L { params.schedule[ell + 1] } else { 1 });
+        let hashed_parent = ar_parent == 16 || ar_parent == 8;
+        let prover_parent = MerkleProver::new(MerkleChannelCfg::new(ar_parent).with_tree_label((ell + 1) as u64).with_leaf_ds());
+
+        if hashed_parent {
+            let mut leaves_parent_h = Vec::with_capacity(lb.parent_indices.len());
+            for &b in &lb.parent_indices {
+                let (fpb, spb) = match parent_maps[ell].get(&b) { Some(&p) => p, None => return false };
+                leaves_parent_h.push(hash_leaf_pair(fpb, spb));
+            }
+            if !prover_parent.verify_single(&proof.roots[ell + 1], &lb.parent_indices, &leaves_parent_h, &lb.parent_proof) {
+                return false;
+            }
+        } else {
+            let mut pairs_parent = Vec::with_capacity(lb.parent_indices.len());
+            for &b in &lb.parent_indices {
+                let (fpb, spb) = match parent_maps[ell].get(&b) { Some(&p) => p, None => return false };
+                pairs_parent.push((fpb, spb));
+            }
+            if !prover_parent.verify_pairs(&proof.roots[ell + 1], &lb.parent_indices, &pairs_parent, &lb.parent_proof) {
+                return false;
+            }
+        }
+    }
+
+    let layer_domains = layer_domains_from_schedule(proof.n0, &params.schedule);
+    for q in 0..params.r {
+        let qp = &proof.queries[q];
+        for ell in 0..L {
+            let rref = &qp.per_layer_refs[ell];
+            let pay = &qp.per_layer_payloads[ell];
+            let (n_layer, _omega_l) = layer_domains[ell];
+            let b = rref.i / params.schedule[ell];
+            if b >= n_layer / params.schedule[ell] { return false; }
+
+            let mixed_leaf = mixed_by_layer.get(&(ell + 1)).and_then(|m| m.get(&rref.parent_index)).copied();
+            let child_leaf_i = CombinedLeaf { f: pay.f_i, s: pay.s_i };
+            if !verify_local_check_fold_batched(child_leaf_i, pay.f_parent_b, mixed_leaf, proof.alpha) {
+                return false;
+            }
+        }
+    }
+
+    {
+        let last_root = proof.roots[L];
+        let ar_last = pick_arity_for_layer(sizes[L], 1);
+        let hashed_last = ar_last == 16 || ar_last == 8;
+        let prover_last = MerkleProver::new(MerkleChannelCfg::new(ar_last).with_tree_label(L as u64).with_leaf_ds());
+        let final_idx = proof.queries[0].final_index;
+        if final_idx != 0 { return false; }
+
+        if hashed_last {
+            let leaf_h = hash_leaf_pair(proof.queries[0].final_pair.0, proof.queries[0].final_pair.1);
+            if !prover_last.verify_single(&last_root, &[final_idx], &[leaf_h], &proof.layer_batches.final_proof) {
+                return false;
+            }
+        } else if !prover_last.verify_pairs(&last_root, &[final_idx], &[proof.queries[0].final_pair], &proof.layer_batches.final_proof) {
+            return false;
+        }
+    }
+
+    true
+}
+
+// ========== Layered commitment: all FRI layers in one mixed-height tree ==========
+//
+// `fri_build_transcript` gives every layer its own root, so `DeepFriProof::roots` is
+// `L+1` field elements and each layer opens an independent multiproof. Here the layers'
+// leaves -- geometrically shrinking in size, one fold step at a time -- are folded into
+// a single binary tree instead: layer 0's leaves sit at the bottom, and whenever the
+// remaining subtree width at some height matches a smaller layer's size, that layer's
+// own leaf hash is mixed into the node at that height (`hash_node_inject`) before
+// continuing upward, so its value is authenticated by the very same path. One root,
+// one union-of-paths multiproof spanning every layer's queried positions (cf. plonky2's
+// `hash::batch_merkle_tree`). Not named `BatchMerkleTree` -- that name already belongs
+// to `merkle::BatchMerkleTree`'s unrelated same-size composite-leaf batching.
+
+fn hash_node_pair(l: F, r: F) -> F {
+    let mut tr = PoseidonTranscript::new(b"FRI/layered/poseidon", transcript_params());
+    tr.absorb_bytes(b"FRI/layered/pair");
+    tr.absorb_field(l);
+    tr.absorb_field(r);
+    tr.challenge(b"node")
+}
+
+fn hash_node_inject(structural: F, extra: F) -> F {
+    let mut tr = PoseidonTranscript::new(b"FRI/layered/poseidon", transcript_params());
+    tr.absorb_bytes(b"FRI/layered/inject");
+    tr.absorb_field(structural);
+    tr.absorb_field(extra);
+    tr.challenge(b"node")
+}
+
+pub struct LayeredFriCommitment {
+    pub height: usize,
+    pub root: F,
+    // levels[0] = n0 leaf hashes; levels[height] = [root]. levels[h] may be a plain
+    // pairwise-hash of levels[h-1], or that hash combined with an injected layer's leaf
+    // when some layer's size equals the width at height h.
+    pub levels: Vec<Vec<F>>,
+    // injected_layer[h-1] = index into `f_layers`/`s_layers` of the layer folded in when
+    // building levels[h], or None if that height is purely structural.
+    pub injected_layer: Vec<Option<usize>>,
+}
+
+impl LayeredFriCommitment {
+    // Recombine the two children at `height - 1` that feed node `index` at `height`,
+    // without the injected extra (if any) -- the "other half" a designated entry point
+    // must reveal since it skips the normal below-level recombination.
+    fn structural_at(&self, height: usize, index: usize) -> F {
+        hash_node_pair(self.levels[height - 1][2 * index], self.levels[height - 1][2 * index + 1])
+    }
+}
+
+pub fn build_layered_fri_commitment(f_layers: &[Vec<F>], s_layers: &[Vec<F>]) -> LayeredFriCommitment {
+    let n0 = f_layers[0].len();
+    assert!(n0.is_power_of_two(), "layered commitment requires power-of-two layer sizes");
+    let height = n0.trailing_zeros() as usize;
+    let layer_sizes: Vec<usize> = f_layers.iter().map(|f| f.len()).collect();
+    // `position` below returns the first (and, given this, only) layer of a given
+    // width: every real fold step strictly shrinks the layer (`fri_fold_layer`
+    // asserts `m >= 2`), so `layer_sizes` -- built from the very layers that step
+    // produced -- is strictly decreasing and can never contain a duplicate width.
+    debug_assert!(
+        layer_sizes.windows(2).all(|w| w[0] > w[1]),
+        "fold layers must be strictly decreasing in size; position-based lookup below assumes no duplicates"
+    );
+
+    let mut levels: Vec<Vec<F>> = Vec::with_capacity(height + 1);
+    levels.push((0..n0).map(|i| hash_leaf_pair(f_layers[0][i], s_layers[0][i])).collect());
+
+    let mut injected_layer = Vec::with_capacity(height);
+    for h in 1..=height {
+        let prev = &levels[h - 1];
+        let width = n0 >> h;
+        let structural: Vec<F> = (0..width).map(|j| hash_node_pair(prev[2 * j], prev[2 * j + 1])).collect();
+
+        let found = layer_sizes.iter().position(|&s| s == width);
+        let cur = match found {
+            Some(idx) => (0..width)
+                .map(|j| hash_node_inject(structural[j], hash_leaf_pair(f_layers[idx][j], s_layers[idx][j])))
+                .collect(),
+            None => structural,
+        };
+        injected_layer.push(found);
+        levels.push(cur);
+    }
+
+    let root = levels[height][0];
+    LayeredFriCommitment { height, root, levels, injected_layer }
+}
+
+#[derive(Clone)]
+pub struct LayeredQueryPayload {
+    pub per_layer_refs: Vec<LayerQueryRef>,
+    pub per_layer_payloads: Vec<LayerOpenPayload>,
+}
+
+pub struct LayeredFriProof {
+    pub root: F,
+    pub height: usize,
+    // Sibling digests needed to climb from height `h` to `h+1`, deduped across every
+    // query and layer -- the union-of-paths multiproof for the whole combined tree.
+    pub sibling_indices: Vec<Vec<usize>>,
+    pub sibling_values: Vec<Vec<F>>,
+    // (height, index, structural) for every designated entry point with height >= 1:
+    // the "other operand" `hash_node_inject` needs, which can't be derived from a
+    // sibling reveal since the entry skips straight past the levels below it.
+    pub entry_structurals: Vec<(usize, usize, F)>,
+    // (height, index, f, s) for every position touched at an injected height, whether
+    // by a query's own entry or merely passed through while climbing to the root.
+    pub injections: Vec<(usize, usize, F, F)>,
+}
+
+pub struct LayeredDeepFriProof {
+    pub tree: LayeredFriProof,
+    pub queries: Vec<LayeredQueryPayload>,
+    pub n0: usize,
+    pub omega0: F,
+}
+
+pub fn deep_fri_prove_layered<B: DeepAliBuilder>(
+    builder: &B, columns: &[Vec<F>], n0: usize, params: &DeepFriParams,
+) -> LayeredDeepFriProof {
+    let domain0 = FriDomain::new_radix2(n0);
+    let f0 = builder.build_f0(columns, n0, domain0);
+
+    let st = fri_build_transcript(
+        f0, domain0,
+        &FriProverParams { schedule: params.schedule.clone(), seed_z: params.seed_z, commitment_arity: params.commitment_arity },
+    );
+
+    let commitment = build_layered_fri_commitment(&st.f_layers, &st.s_layers);
+    let roots_seed = fs_seed_from_roots(&[commitment.root]);
+
+    // Reuse the existing per-layer index sampling unchanged; only the authentication
+    // scheme against those indices changes.
+    let (refs_only, _roots, _old_batches) = fri_prove_queries(&st, params.r, roots_seed);
+
+    let L = params.schedule.len();
+    let layer_sizes = layer_sizes_from_schedule(n0, &params.schedule);
+    let height = commitment.height;
+    let height_of = |size: usize| height - (size.trailing_zeros() as usize);
+
+    let mut queries: Vec<LayeredQueryPayload> = Vec::with_capacity(params.r);
+    let mut entries: Vec<(usize, usize)> = Vec::new();
+    for q in 0..params.r {
+        let mut per_layer_payloads = Vec::with_capacity(L);
+        for ell in 0..L {
+            let rref = &refs_only[q].per_layer_refs[ell];
+            let f_i = st.f_layers[ell][rref.i];
+            let s_i = st.s_layers[ell][rref.i];
+            let f_parent_b = st.f_layers[ell + 1][rref.parent_index];
+            let s_parent_b = st.s_layers[ell + 1][rref.parent_index];
+            per_layer_payloads.push(LayerOpenPayload { f_i, s_i, f_parent_b, s_parent_b });
+
+            entries.push((height_of(layer_sizes[ell]), rref.i));
+            entries.push((height_of(layer_sizes[ell + 1]), rref.parent_index));
+        }
+        queries.push(LayeredQueryPayload { per_layer_refs: refs_only[q].per_layer_refs.clone(), per_layer_payloads });
+    }
+
+    // Simulate the climb from every entry point to the root, collecting the sibling and
+    // injection reveals the verifier will need -- shared across every query and layer.
+    use std::collections::BTreeSet;
+    let mut live: BTreeSet<usize> = BTreeSet::new();
+    let mut sibling_sets: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); height];
+    let mut injection_positions: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); height + 1];
+
+    for h in 0..height {
+        for &(eh, ei) in &entries {
+            if eh == h { live.insert(ei); }
+        }
+        for &i in &live { sibling_sets[h].insert(i ^ 1); }
+        let mut next: BTreeSet<usize> = live.iter().map(|&i| i >> 1).collect();
+        for &(eh, ei) in &entries {
+            if eh == h + 1 { next.insert(ei); }
+        }
+        if commitment.injected_layer[h].is_some() {
+            for &j in &next { injection_positions[h + 1].insert(j); }
+        }
+        live = next;
+    }
+
+    let sibling_indices: Vec<Vec<usize>> = sibling_sets.iter().map(|s| s.iter().copied().collect()).collect();
+    let sibling_values: Vec<Vec<F>> = sibling_indices
+        .iter()
+        .enumerate()
+        .map(|(h, idxs)| idxs.iter().map(|&i| commitment.levels[h][i]).collect())
+        .collect();
+
+    let mut entry_structurals = Vec::new();
+    for &(eh, ei) in &entries {
+        if eh >= 1 {
+            entry_structurals.push((eh, ei, commitment.structural_at(eh, ei)));
+        }
+    }
+    entry_structurals.sort_unstable_by_key(|&(h, i, _)| (h, i));
+    entry_structurals.dedup_by_key(|t| (t.0, t.1));
+
+    let mut injections = Vec::new();
+    for h in 1..=height {
+        if let Some(layer_idx) = commitment.injected_layer[h - 1] {
+            for &j in &injection_positions[h] {
+                injections.push((h, j, st.f_layers[layer_idx][j], st.s_layers[layer_idx][j]));
+            }
+        }
+    }
+
+    LayeredDeepFriProof {
+        tree: LayeredFriProof {
+            root: commitment.root,
+            height,
+            sibling_indices,
+            sibling_values,
+            entry_structurals,
+            injections,
+        },
+        queries,
+        n0,
+        omega0: domain0.omega,
+    }
+}
+
+// Climbs from the designated entry `(h0, i0)` -- whose own (f, s) pair is `own_f`/
+// `own_s` -- up to the root, checking every step against the revealed siblings and
+// injections, and caching node values so shared ancestors are only computed once
+// across the many calls this makes per proof.
+#[allow(clippy::too_many_arguments)]
+fn climb_layered_to_root(
+    sib_maps: &[std::collections::BTreeMap<usize, F>],
+    injection_map: &std::collections::BTreeMap<(usize, usize), (F, F)>,
+    entry_structural_map: &std::collections::BTreeMap<(usize, usize), F>,
+    injected_height: &[bool],
+    node_cache: &mut std::collections::BTreeMap<(usize, usize), F>,
+    root: F, height: usize,
+    h0: usize, i0: usize, own_f: F, own_s: F,
+) -> bool {
+    let mut val = if h0 == 0 {
+        hash_leaf_pair(own_f, own_s)
+    } else {
+        let structural = match entry_structural_map.get(&(h0, i0)) {
+            Some(&v) => v,
+            None => return false,
+        };
+        hash_node_inject(structural, hash_leaf_pair(own_f, own_s))
+    };
+
+    if let Some(&cached) = node_cache.get(&(h0, i0)) {
+        if cached != val { return false; }
+    } else {
+        node_cache.insert((h0, i0), val);
+    }
+
+    let mut h = h0;
+    let mut i = i0;
+    while h < height {
+        let sib = match sib_maps[h].get(&(i ^ 1)) {
+            Some(&v) => v,
+            None => return false,
+        };
+        let (left, right) = if i % 2 == 0 { (val, sib) } else { (sib, val) };
+        let structural = hash_node_pair(left, right);
+        let parent_h = h + 1;
+        let parent_i = i >> 1;
+        val = if injected_height[parent_h] {
+            let (f, s) = match injection_map.get(&(parent_h, parent_i)) {
+                Some(&pair) => pair,
+                None => return false,
+            };
+            hash_node_inject(structural, hash_leaf_pair(f, s))
+        } else {
+            structural
+        };
+
+        if let Some(&cached) = node_cache.get(&(parent_h, parent_i)) {
+            if cached != val { return false; }
+        } else {
+            node_cache.insert((parent_h, parent_i), val);
+        }
+        h = parent_h;
+        i = parent_i;
+    }
+    val == root
+}
+
+pub fn deep_fri_verify_layered(params: &DeepFriParams, proof: &LayeredDeepFriProof) -> bool {
+    let L = params.schedule.len();
+    if proof.queries.len() != params.r { return false; }
+    if proof.n0 == 0 || !proof.n0.is_power_of_two() { return false; }
+    let height = proof.tree.height;
+    if proof.n0.trailing_zeros() as usize != height { return false; }
+    if proof.tree.sibling_indices.len() != height || proof.tree.sibling_values.len() != height {
+        return false;
+    }
+
+    let sizes = layer_sizes_from_schedule(proof.n0, &params.schedule);
+    let height_of = |size: usize| -> Option<usize> {
+        if size == 0 || !size.is_power_of_two() { return None; }
+        height.checked_sub(size.trailing_zeros() as usize)
+    };
+
+    use std::collections::BTreeMap;
+    let mut sib_maps: Vec<BTreeMap<usize, F>> = Vec::with_capacity(height);
+    for h in 0..height {
+        if proof.tree.sibling_indices[h].len() != proof.tree.sibling_values[h].len() {
+            return false;
+        }
+        let mut m = BTreeMap::new();
+        for (&i, &v) in proof.tree.sibling_indices[h].iter().zip(proof.tree.sibling_values[h].iter()) {
+            m.insert(i, v);
+        }
+        sib_maps.push(m);
+    }
+
+    let mut entry_structural_map: BTreeMap<(usize, usize), F> = BTreeMap::new();
+    for &(h, i, v) in &proof.tree.entry_structurals {
+        entry_structural_map.insert((h, i), v);
+    }
+    let mut injection_map: BTreeMap<(usize, usize), (F, F)> = BTreeMap::new();
+    for &(h, i, f, s) in &proof.tree.injections {
+        injection_map.insert((h, i), (f, s));
+    }
+
+    // Which heights carry an injected layer, recomputed purely from the public
+    // schedule -- mirrors `build_layered_fri_commitment`'s own bookkeeping.
+    let mut injected_height = vec![false; height + 1];
+    for &sz in &sizes {
+        if let Some(h) = height_of(sz) {
+            if h >= 1 { injected_height[h] = true; }
+        }
+    }
+
+    // Local fold-consistency checks: enforce s_i == f_parent[b], same as the
+    // single-root verifier.
+    let layer_domains = layer_domains_from_schedule(proof.n0, &params.schedule);
+    for q in 0..params.r {
+        let qp = &proof.queries[q];
+        if qp.per_layer_refs.len() != L || qp.per_layer_payloads.len() != L {
+            return false;
+        }
+        for ell in 0..L {
+            let rref = &qp.per_layer_refs[ell];
+            let pay = &qp.per_layer_payloads[ell];
+            let (n_layer, _omega_l) = layer_domains[ell];
+            let child_leaf_i = CombinedLeaf { f: pay.f_i, s: pay.s_i };
+            if !verify_local_check_fold(rref.i, params.schedule[ell], n_layer, child_leaf_i, pay.f_parent_b) {
+                return false;
+            }
+        }
+    }
+
+    // Authenticate every queried (layer, position) against the single combined root.
+    let mut node_cache: BTreeMap<(usize, usize), F> = BTreeMap::new();
+    for q in 0..params.r {
+        let qp = &proof.queries[q];
+        for ell in 0..L {
+            let rref = &qp.per_layer_refs[ell];
+            let pay = &qp.per_layer_payloads[ell];
+
+            let h_child = match height_of(sizes[ell]) { Some(h) => h, None => return false };
+            if !climb_layered_to_root(
+                &sib_maps, &injection_map, &entry_structural_map, &injected_height, &mut node_cache,
+                proof.tree.root, height, h_child, rref.i, pay.f_i, pay.s_i,
+            ) {
+                return false;
+            }
+
+            let h_parent = match height_of(sizes[ell + 1]) { Some(h) => h, None => return false };
+            if !climb_layered_to_root(
+                &sib_maps, &injection_map, &entry_structural_map, &injected_height, &mut node_cache,
+                proof.tree.root, height, h_parent, rref.parent_index, pay.f_parent_b, pay.s_parent_b,
+            ) {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+// ========== Canonical wire format ==========
+//
+// Hand-rolled binary encoding for `DeepFriProof`, mirroring `MerkleProof::serialize` in
+// the merkle crate: a leading `SiblingOrder` tag (propagated into every embedded
+// `MerkleProof`), length-prefixed arrays, and fixed-width 32-byte field elements.
+// `deserialize` validates every length against the remaining buffer and returns `None`
+// on a short read instead of panicking, so a proof truncated mid-flight (e.g. missing
+// its final-layer proof) is rejected cleanly.
+
+fn fw_u8(buf: &mut Vec<u8>, v: u8) {
+    buf.push(v);
+}
+
+fn fw_u64(buf: &mut Vec<u8>, v: u64) {
+    buf.extend_from_slice(&v.to_le_bytes());
+}
+
+fn fw_field(buf: &mut Vec<u8>, v: F) {
+    v.serialize_with_mode(buf, Compress::Yes).expect("writing to a Vec<u8> cannot fail");
+}
+
+fn fw_merkle_proof(buf: &mut Vec<u8>, proof: &MerkleProof, order: SiblingOrder) {
+    let encoded = proof.serialize(order);
+    fw_u64(buf, encoded.len() as u64);
+    buf.extend_from_slice(&encoded);
+}
+
+fn fr_u8(bytes: &mut &[u8]) -> Option<u8> {
+    let (&first, rest) = bytes.split_first()?;
+    *bytes = rest;
+    Some(first)
+}
+
+fn fr_u64(bytes: &mut &[u8]) -> Option<u64> {
+    if bytes.len() < 8 {
+        return None;
+    }
+    let (head, tail) = bytes.split_at(8);
+    *bytes = tail;
+    Some(u64::from_le_bytes(head.try_into().unwrap()))
+}
+
+fn fr_field(bytes: &mut &[u8]) -> Option<F> {
+    F::deserialize_with_mode(&mut *bytes, Compress::Yes, Validate::Yes).ok()
+}
+
+fn fr_bytes<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if bytes.len() < len {
+        return None;
+    }
+    let (head, tail) = bytes.split_at(len);
+    *bytes = tail;
+    Some(head)
+}
+
+fn fr_merkle_proof(bytes: &mut &[u8]) -> Option<MerkleProof> {
+    let len = fr_u64(bytes)? as usize;
+    let slice = fr_bytes(bytes, len)?;
+    MerkleProof::deserialize(slice)
+}
+
+impl DeepFriParams {
+    // `DeepFriParams` is small and has no embedded `MerkleProof`s, so unlike
+    // `DeepFriProof::serialize` it doesn't need a `SiblingOrder` tag.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        fw_u64(&mut buf, self.schedule.len() as u64);
+        for &s in &self.schedule {
+            fw_u64(&mut buf, s as u64);
+        }
+        fw_u64(&mut buf, self.r as u64);
+        fw_u64(&mut buf, self.seed_z);
+        // `Option<usize>` as a presence byte + value, rather than folding "no override"
+        // into a sentinel arity (0 or 1 are both otherwise-meaningless but not obviously
+        // unused, e.g. 1 would silently mean "no folding" under `pick_arity_for_layer`).
+        match self.commitment_arity {
+            Some(arity) => {
+                fw_u8(&mut buf, 1);
+                fw_u64(&mut buf, arity as u64);
+            }
+            None => fw_u8(&mut buf, 0),
+        }
+        fw_u64(&mut buf, self.pow_bits as u64);
+        buf
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        let mut cur = bytes;
+        let n_schedule = fr_u64(&mut cur)? as usize;
+        let mut schedule = Vec::with_capacity(n_schedule);
+        for _ in 0..n_schedule {
+            schedule.push(fr_u64(&mut cur)? as usize);
+        }
+        let r = fr_u64(&mut cur)? as usize;
+        let seed_z = fr_u64(&mut cur)?;
+        let commitment_arity = match fr_u8(&mut cur)? {
+            0 => None,
+            1 => Some(fr_u64(&mut cur)? as usize),
+            _ => return None,
+        };
+        let pow_bits = fr_u64(&mut cur)? as u32;
+        Some(DeepFriParams { schedule, r, seed_z, commitment_arity, pow_bits })
+    }
+}
+
+impl DeepFriProof {
+    pub fn serialize(&self, order: SiblingOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        fw_u8(&mut buf, match order { SiblingOrder::DepthFirst => 0, SiblingOrder::Reversed => 1 });
+        fw_u64(&mut buf, self.n0 as u64);
+        fw_field(&mut buf, self.omega0);
+        fw_u64(&mut buf, self.nonce);
+
+        fw_u64(&mut buf, self.roots.len() as u64);
+        for &r in &self.roots {
+            fw_field(&mut buf, r);
+        }
+
+        fw_u64(&mut buf, self.layer_batches.layers.len() as u64);
+        for lb in &self.layer_batches.layers {
+            fw_u8(&mut buf, lb.hashed_leaves as u8);
+            fw_u64(&mut buf, lb.child_indices.len() as u64);
+            for &i in &lb.child_indices {
+                fw_u64(&mut buf, i as u64);
+            }
+            fw_merkle_proof(&mut buf, &lb.child_proof, order);
+            fw_u64(&mut buf, lb.parent_indices.len() as u64);
+            for &i in &lb.parent_indices {
+                fw_u64(&mut buf, i as u64);
+            }
+            fw_merkle_proof(&mut buf, &lb.parent_proof, order);
+        }
+        fw_merkle_proof(&mut buf, &self.layer_batches.final_proof, order);
+
+        fw_u64(&mut buf, self.queries.len() as u64);
+        for q in &self.queries {
+            fw_u64(&mut buf, q.per_layer_refs.len() as u64);
+            for rref in &q.per_layer_refs {
+                fw_u64(&mut buf, rref.i as u64);
+                fw_u64(&mut buf, rref.child_pos as u64);
+                fw_u64(&mut buf, rref.parent_index as u64);
+                fw_u64(&mut buf, rref.parent_pos as u64);
+            }
+            fw_u64(&mut buf, q.per_layer_payloads.len() as u64);
+            for pay in &q.per_layer_payloads {
+                fw_field(&mut buf, pay.f_i);
+                fw_field(&mut buf, pay.s_i);
+                fw_field(&mut buf, pay.f_parent_b);
+                fw_field(&mut buf, pay.s_parent_b);
+            }
+            fw_u64(&mut buf, q.final_index as u64);
+            fw_field(&mut buf, q.final_pair.0);
+            fw_field(&mut buf, q.final_pair.1);
+        }
+
+        buf
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        let mut cur = bytes;
+        // Just a validity check: each embedded `MerkleProof` carries its own order tag
+        // and is self-describing, so the top-level tag doesn't need to be threaded
+        // through here -- it only needs to be a value `serialize` could have written.
+        match fr_u8(&mut cur)? {
+            0 | 1 => {}
+            _ => return None,
+        }
+
+        let n0 = fr_u64(&mut cur)? as usize;
+        let omega0 = fr_field(&mut cur)?;
+        let nonce = fr_u64(&mut cur)?;
+
+        let n_roots = fr_u64(&mut cur)? as usize;
+        let mut roots = Vec::with_capacity(n_roots);
+        for _ in 0..n_roots {
+            roots.push(fr_field(&mut cur)?);
+        }
+
+        let n_layers = fr_u64(&mut cur)? as usize;
+        let mut layers = Vec::with_capacity(n_layers);
+        for _ in 0..n_layers {
+            let hashed_leaves = fr_u8(&mut cur)? != 0;
+
+            let n_child = fr_u64(&mut cur)? as usize;
+            let mut child_indices = Vec::with_capacity(n_child);
+            for _ in 0..n_child {
+                child_indices.push(fr_u64(&mut cur)? as usize);
+            }
+            let child_proof = fr_merkle_proof(&mut cur)?;
+
+            let n_parent = fr_u64(&mut cur)? as usize;
+            let mut parent_indices = Vec::with_capacity(n_parent);
+            for _ in 0..n_parent {
+                parent_indices.push(fr_u64(&mut cur)? as usize);
+            }
+            let parent_proof = fr_merkle_proof(&mut cur)?;
+
+            layers.push(LayerBatchProof { hashed_leaves, child_indices, child_proof, parent_indices, parent_proof });
+        }
+        let final_proof = fr_merkle_proof(&mut cur)?;
+
+        let n_queries = fr_u64(&mut cur)? as usize;
+        let mut queries = Vec::with_capacity(n_queries);
+        for _ in 0..n_queries {
+            let n_refs = fr_u64(&mut cur)? as usize;
+            let mut per_layer_refs = Vec::with_capacity(n_refs);
+            for _ in 0..n_refs {
+                let i = fr_u64(&mut cur)? as usize;
+                let child_pos = fr_u64(&mut cur)? as usize;
+                let parent_index = fr_u64(&mut cur)? as usize;
+                let parent_pos = fr_u64(&mut cur)? as usize;
+                per_layer_refs.push(LayerQueryRef { i, child_pos, parent_index, parent_pos });
+            }
+
+            let n_payloads = fr_u64(&mut cur)? as usize;
+            let mut per_layer_payloads = Vec::with_capacity(n_payloads);
+            for _ in 0..n_payloads {
+                let f_i = fr_field(&mut cur)?;
+                let s_i = fr_field(&mut cur)?;
+                let f_parent_b = fr_field(&mut cur)?;
+                let s_parent_b = fr_field(&mut cur)?;
+                per_layer_payloads.push(LayerOpenPayload { f_i, s_i, f_parent_b, s_parent_b });
+            }
+
+            let final_index = fr_u64(&mut cur)? as usize;
+            let final_pair_0 = fr_field(&mut cur)?;
+            let final_pair_1 = fr_field(&mut cur)?;
+
+            queries.push(FriQueryPayload {
+                per_layer_refs,
+                per_layer_payloads,
+                final_index,
+                final_pair: (final_pair_0, final_pair_1),
+            });
+        }
+
+        Some(DeepFriProof {
+            roots,
+            layer_batches: FriLayerBatches { layers, final_proof },
+            queries,
+            n0,
+            omega0,
+            nonce,
+        })
+    }
+}
+
+// Exact wire length of `DeepFriProof::serialize`, so callers can preallocate a buffer
+// (or a network frame) of the right size instead of guessing.
+pub fn deep_fri_proof_size_bytes(p: &DeepFriProof) -> usize {
+    p.serialize(SiblingOrder::DepthFirst).len()
+}
+
+// ========== Optional erasure-coded proof sharding for lossy transport ==========
+//
+// Splits a serialized `DeepFriProof` into `data_shards` equal-size pieces plus
+// `coding_shards` Reed-Solomon parity pieces, so a node on a multicast/gossip channel
+// that drops individual datagrams can reconstruct the full proof from any
+// `data_shards`-of-`(data_shards + coding_shards)` it happens to receive. Gated behind
+// the `erasure_coding` feature since it pulls in `reed_solomon_erasure`, the same way
+// the `parallel` feature above gates the optional `rayon` dependency.
+#[cfg(feature = "erasure_coding")]
+mod shard {
+    use super::*;
+    use reed_solomon_erasure::galois_8::ReedSolomon;
+
+    // Per-shard header: which piece this is, the shard layout, and an integrity tag
+    // derived from the proof's own recovered layer roots (`DeepFriProof::recover_layer_roots`)
+    // rather than from the shard bytes themselves, so a node can tell a corrupted
+    // shard from a merely-incomplete shard set before attempting reconstruction.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct ProofShard {
+        pub index: u32,
+        pub data_shards: u32,
+        pub coding_shards: u32,
+        pub root_tag: [u8; 32],
+        pub payload: Vec<u8>,
+    }
+
+    fn root_tag_bytes(proof: &DeepFriProof, params: &DeepFriParams) -> Option<[u8; 32]> {
+        let roots = proof.recover_layer_roots(params)?;
+        let tag_field = fs_seed_from_roots(&roots);
+        let mut buf = [0u8; 32];
+        tag_field
+            .serialize_compressed(&mut buf[..])
+            .expect("Pallas Fr is 32 bytes compressed");
+        Some(buf)
+    }
+
+    // Splits `proof` (encoded via `DeepFriProof::serialize`) into `data_shards` data
+    // pieces and `coding_shards` Reed-Solomon parity pieces. Returns `None` if the
+    // proof's layer roots don't recover -- a malformed proof has nothing honest to
+    // stamp on its shards as an integrity tag.
+    pub fn shard_proof(
+        proof: &DeepFriProof,
+        params: &DeepFriParams,
+        data_shards: usize,
+        coding_shards: usize,
+        order: SiblingOrder,
+    ) -> Option<Vec<ProofShard>> {
+        let root_tag = root_tag_bytes(proof, params)?;
+        let encoded = proof.serialize(order);
+
+        // Reed-Solomon needs every shard the same length; prefix the true length so
+        // the zero padding added to reach that length can be stripped on reconstruction.
+        let mut framed = Vec::with_capacity(8 + encoded.len());
+        framed.extend_from_slice(&(encoded.len() as u64).to_le_bytes());
+        framed.extend_from_slice(&encoded);
+
+        let shard_len = (framed.len() + data_shards - 1) / data_shards;
+        framed.resize(shard_len * data_shards, 0u8);
+
+        let mut shards: Vec<Vec<u8>> = framed.chunks(shard_len).map(|c| c.to_vec()).collect();
+        shards.resize(data_shards + coding_shards, vec![0u8; shard_len]);
+
+        let rs = ReedSolomon::new(data_shards, coding_shards).ok()?;
+        rs.encode(&mut shards).ok()?;
+
+        Some(
+            shards
+                .into_iter()
+                .enumerate()
+                .map(|(index, payload)| ProofShard {
+                    index: index as u32,
+                    data_shards: data_shards as u32,
+                    coding_shards: coding_shards as u32,
+                    root_tag,
+                    payload,
+                })
+                .collect(),
+        )
+    }
+
+    // Reconstructs the original proof from any `data_shards`-of-total shards, provided
+    // they all agree on `root_tag` -- a mismatched tag is treated as corruption and
+    // rejected outright rather than fed into reconstruction.
+    pub fn reconstruct_proof(shards: &[ProofShard]) -> Option<DeepFriProof> {
+        if shards.is_empty() { return None; }
+        let data_shards = shards[0].data_shards as usize;
+        let coding_shards = shards[0].coding_shards as usize;
+        let root_tag = shards[0].root_tag;
+        let total = data_shards + coding_shards;
+
+        if shards.iter().any(|s| {
+            s.data_shards as usize != data_shards
+                || s.coding_shards as usize != coding_shards
+                || s.root_tag != root_tag
+        }) {
+            return None;
+        }
+
+        let mut slots: Vec<Option<Vec<u8>>> = vec![None; total];
+        for s in shards {
+            let idx = s.index as usize;
+            if idx >= total { return None; }
+            slots[idx] = Some(s.payload.clone());
+        }
+
+        let rs = ReedSolomon::new(data_shards, coding_shards).ok()?;
+        rs.reconstruct(&mut slots).ok()?;
+
+        let mut framed = Vec::new();
+        for slot in slots.into_iter().take(data_shards) {
+            framed.extend_from_slice(&slot?);
+        }
+
+        if framed.len() < 8 { return None; }
+        let (len_bytes, rest) = framed.split_at(8);
+        let true_len = u64::from_le_bytes(len_bytes.try_into().ok()?) as usize;
+        if true_len > rest.len() { return None; }
+
+        DeepFriProof::deserialize(&rest[..true_len])
+    }
+}
+
+#[cfg(feature = "erasure_coding")]
+pub use shard::{reconstruct_proof, shard_proof, ProofShard};
+
+#[cfg(test)]
+mod fft2adic_tests {
+    use super::*;
+
+    #[test]
+    fn new_two_adic_matches_new_radix2() {
+        for size in [2usize, 4, 16, 1024] {
+            let a = FriDomain::new_radix2(size);
+            let b = FriDomain::new_two_adic(size);
+            assert_eq!(a.omega, b.omega);
+            assert_eq!(a.size, b.size);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "power of two")]
+    fn new_two_adic_rejects_non_power_of_two_size() {
+        let _ = FriDomain::new_two_adic(3);
+    }
+}
+
+#[cfg(test)]
+mod grinding_tests {
+    use super::*;
+
+    fn builder_and_columns(n0: usize, seed: u64) -> (DeepAliRealBuilder, Vec<Vec<F>>) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let phi: Vec<F> = (0..n0).map(|_| F::from(rng.gen::<u64>())).collect();
+        (DeepAliRealBuilder::default(), vec![phi])
+    }
+
+    #[test]
+    fn grind_nonce_meets_the_requested_difficulty() {
+        let roots_seed = F::from(123_456u64);
+        for pow_bits in [0u32, 4, 8] {
+            let nonce = grind_nonce(roots_seed, pow_bits);
+            let digest = grind_seed(roots_seed, nonce);
+            assert!(leading_zero_bits(&digest) >= pow_bits);
+        }
+    }
+
+    #[test]
+    fn prove_and_verify_roundtrip_with_grinding_enabled() {
+        let n0 = 1usize << 11;
+        let (builder, columns) = builder_and_columns(n0, 42);
+        let params = DeepFriParams {
+            schedule: vec![16, 16, 8],
+            r: 8,
+            seed_z: 0xA11CE,
+            commitment_arity: None,
+            pow_bits: 8,
+        };
+
+        let proof = deep_fri_prove(&builder, &columns, n0, &params);
+        assert!(deep_fri_verify(&params, &proof).is_ok());
+    }
+
+    #[test]
+    fn verify_rejects_a_tampered_nonce() {
+        let n0 = 1usize << 11;
+        let (builder, columns) = builder_and_columns(n0, 7);
+        let params = DeepFriParams {
+            schedule: vec![16, 16, 8],
+            r: 8,
+            seed_z: 0xA11CE,
+            commitment_arity: None,
+            pow_bits: 8,
+        };
+
+        let mut proof = deep_fri_prove(&builder, &columns, n0, &params);
+        proof.nonce = proof.nonce.wrapping_add(1);
+        assert_eq!(deep_fri_verify(&params, &proof), Err(VerifyError::GrindingInsufficient));
+    }
+
+    #[test]
+    fn queries_for_security_accounts_for_grinding_bits() {
+        // rate = 1/8 => log2(1/rate) = 3 bits/query.
+        assert_eq!(queries_for_security(90, 1.0 / 8.0, 0), 30);
+        assert_eq!(queries_for_security(90, 1.0 / 8.0, 30), 20);
+        assert_eq!(queries_for_security(90, 1.0 / 8.0, 90), 0);
+        assert_eq!(queries_for_security(90, 1.0 / 8.0, 120), 0);
+    }
+}
+
+#[cfg(test)]
+mod low_degree_test_tests {
+    use super::*;
+
+    #[test]
+    fn prove_and_verify_low_degree_roundtrip() {
+        let n0 = 1usize << 11;
+        let mut rng = StdRng::seed_from_u64(314);
+        let f0_eval: Vec<F> = (0..n0).map(|_| F::from(rng.gen::<u64>())).collect();
+        let domain = FriDomain::new_radix2(n0);
+
+        let params = DeepFriParams {
+            schedule: vec![2; 11],
+            r: 8,
+            seed_z: 0xF12_1234,
+            commitment_arity: None,
+            pow_bits: 0,
+        };
+
+        let proof = prove_low_degree(&domain, &f0_eval, &params);
+        assert!(verify_low_degree(&params, &proof));
+    }
+
+    #[test]
+    fn verify_low_degree_rejects_a_tampered_query_opening() {
+        let n0 = 1usize << 11;
+        let mut rng = StdRng::seed_from_u64(271);
+        let f0_eval: Vec<F> = (0..n0).map(|_| F::from(rng.gen::<u64>())).collect();
+        let domain = FriDomain::new_radix2(n0);
+
+        let params = DeepFriParams {
+            schedule: vec![2; 11],
+            r: 8,
+            seed_z: 0xF12_1234,
+            commitment_arity: None,
+            pow_bits: 0,
+        };
+
+        let mut proof = prove_low_degree(&domain, &f0_eval, &params);
+        proof.queries[0].per_layer_payloads[0].f_i += F::from(1u64);
+        assert!(!verify_low_degree(&params, &proof));
+    }
+}
+
+#[cfg(test)]
+mod batch_fri_tests {
+    use super::*;
+
+    // `main` matches `domain0` (size 2048); `mixed` matches the first fold layer's
+    // size (128, after one arity-16 fold), so it's the one polynomial actually
+    // exercised via `mix_into`/`MixedPolyOpening` -- same schedule/n0 already proven
+    // out by `grinding_tests::prove_and_verify_roundtrip_with_grinding_enabled`.
+    fn sample_batch_polys(seed: u64) -> Vec<Vec<F>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let main: Vec<F> = (0..2048).map(|_| F::from(rng.gen::<u64>())).collect();
+        let mixed: Vec<F> = (0..128).map(|_| F::from(rng.gen::<u64>())).collect();
+        vec![main, mixed]
+    }
+
+    fn sample_batch_params() -> DeepFriParams {
+        DeepFriParams {
+            schedule: vec![16, 16, 8],
+            r: 8,
+            seed_z: 0xBA7C4,
+            commitment_arity: None,
+            pow_bits: 0,
+        }
+    }
+
+    #[test]
+    fn prove_and_verify_batch_fri_roundtrip() {
+        let polys = sample_batch_polys(11);
+        let params = sample_batch_params();
+        let proof = batch_fri_prove(&polys, 2048, &params);
+        assert!(batch_fri_verify(&params, &proof));
+    }
+
+    #[test]
+    fn batch_fri_verify_rejects_a_tampered_mixed_opening() {
+        let polys = sample_batch_polys(13);
+        let params = sample_batch_params();
+        let mut proof = batch_fri_prove(&polys, 2048, &params);
+        proof.mixed_openings[0].values[0] += F::from(1u64);
+        assert!(!batch_fri_verify(&params, &proof));
+    }
+
+    #[test]
+    fn batch_fri_verify_rejects_a_wrong_alpha() {
+        let polys = sample_batch_polys(17);
+        let params = sample_batch_params();
+        let mut proof = batch_fri_prove(&polys, 2048, &params);
+        proof.alpha += F::from(1u64);
+        assert!(!batch_fri_verify(&params, &proof));
+    }
+}
+
+#[cfg(test)]
+mod layered_fri_tests {
+    use super::*;
+
+    fn builder_and_columns(n0: usize, seed: u64) -> (DeepAliRealBuilder, Vec<Vec<F>>) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let phi: Vec<F> = (0..n0).map(|_| F::from(rng.gen::<u64>())).collect();
+        (DeepAliRealBuilder::default(), vec![phi])
+    }
+
+    fn sample_layered_params() -> DeepFriParams {
+        DeepFriParams {
+            schedule: vec![16, 16, 8],
+            r: 8,
+            seed_z: 0xFEED5EED,
+            commitment_arity: None,
+            pow_bits: 0,
+        }
+    }
+
+    #[test]
+    fn prove_and_verify_layered_roundtrip() {
+        let n0 = 1usize << 11;
+        let (builder, columns) = builder_and_columns(n0, 19);
+        let params = sample_layered_params();
+
+        let proof = deep_fri_prove_layered(&builder, &columns, n0, &params);
+        assert!(deep_fri_verify_layered(&params, &proof));
+    }
+
+    #[test]
+    fn verify_layered_rejects_a_tampered_injection() {
+        let n0 = 1usize << 11;
+        let (builder, columns) = builder_and_columns(n0, 23);
+        let params = sample_layered_params();
+
+        let mut proof = deep_fri_prove_layered(&builder, &columns, n0, &params);
+        assert!(!proof.tree.injections.is_empty());
+        proof.tree.injections[0].2 += F::from(1u64);
+        assert!(!deep_fri_verify_layered(&params, &proof));
+    }
+
+    #[test]
+    fn verify_layered_rejects_a_tampered_root() {
+        let n0 = 1usize << 11;
+        let (builder, columns) = builder_and_columns(n0, 29);
+        let params = sample_layered_params();
+
+        let mut proof = deep_fri_prove_layered(&builder, &columns, n0, &params);
+        proof.tree.root += F::from(1u64);
+        assert!(!deep_fri_verify_layered(&params, &proof));
+    }
+}
+
+#[cfg(all(test, feature = "erasure_coding"))]
+mod shard_tests {
+    use super::*;
+
+    fn sample_proof(seed: u64) -> (DeepFriProof, DeepFriParams) {
+        let n0 = 1usize << 11;
+        let mut rng = StdRng::seed_from_u64(seed);
+        let phi: Vec<F> = (0..n0).map(|_| F::from(rng.gen::<u64>())).collect();
+        let columns = vec![phi];
+        let builder = DeepAliRealBuilder::default();
+        let params = DeepFriParams {
+            schedule: vec![16, 16, 8],
+            r: 8,
+            seed_z: 0x5EEDED,
+            commitment_arity: None,
+            pow_bits: 0,
+        };
+        let proof = deep_fri_prove(&builder, &columns, n0, &params);
+        (proof, params)
+    }
+
+    #[test]
+    fn shard_and_reconstruct_roundtrip_from_exactly_enough_shards() {
+        let (proof, params) = sample_proof(101);
+        let data_shards = 6;
+        let coding_shards = 3;
+        let shards = shard_proof(&proof, &params, data_shards, coding_shards, SiblingOrder::DepthFirst)
+            .expect("well-formed proof shards");
+
+        // Drop `coding_shards` of them (simulating erasures) but keep exactly `data_shards`.
+        let surviving: Vec<ProofShard> = shards.into_iter().skip(coding_shards).collect();
+        assert_eq!(surviving.len(), data_shards);
+
+        let recovered = reconstruct_proof(&surviving).expect("enough shards to decode");
+        assert_eq!(
+            recovered.serialize(SiblingOrder::DepthFirst),
+            proof.serialize(SiblingOrder::DepthFirst)
+        );
+    }
+
+    #[test]
+    fn reconstruct_fails_with_too_few_shards() {
+        let (proof, params) = sample_proof(103);
+        let data_shards = 6;
+        let coding_shards = 3;
+        let shards = shard_proof(&proof, &params, data_shards, coding_shards, SiblingOrder::DepthFirst)
+            .expect("well-formed proof shards");
+
+        // Only `data_shards - 1` survive -- not enough left to decode.
+        let surviving: Vec<ProofShard> = shards.into_iter().skip(coding_shards + 1).collect();
+        assert_eq!(surviving.len(), data_shards - 1);
+        assert!(reconstruct_proof(&surviving).is_none());
+    }
+}
+
+#[cfg(test)]
+mod verify_batch_tests {
+    use super::*;
+
+    fn sample_columns_per_proof(n0: usize, count: usize, seed: u64) -> Vec<Vec<Vec<F>>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..count)
+            .map(|_| vec![(0..n0).map(|_| F::from(rng.gen::<u64>())).collect()])
+            .collect()
+    }
+
+    fn sample_batch_verify_params() -> DeepFriParams {
+        DeepFriParams {
+            schedule: vec![16, 16, 8],
+            r: 8,
+            seed_z: 0xBA7C4,
+            commitment_arity: None,
+            pow_bits: 0,
+        }
+    }
+
+    #[test]
+    fn prove_many_and_verify_batch_roundtrip() {
+        let n0 = 1usize << 11;
+        let builder = DeepAliRealBuilder::default();
+        let columns_per_proof = sample_columns_per_proof(n0, 3, 41);
+        let params = sample_batch_verify_params();
+
+        let proofs = deep_fri_prove_many(&builder, &columns_per_proof, n0, &params);
+        assert!(deep_fri_verify_batch(&params, &proofs));
+    }
+
+    #[test]
+    fn verify_batch_rejects_a_tampered_root_in_one_proof() {
+        let n0 = 1usize << 11;
+        let builder = DeepAliRealBuilder::default();
+        let columns_per_proof = sample_columns_per_proof(n0, 3, 43);
+        let params = sample_batch_verify_params();
+
+        let mut proofs = deep_fri_prove_many(&builder, &columns_per_proof, n0, &params);
+        proofs[1].roots[0] += F::from(1u64);
+        assert!(!deep_fri_verify_batch(&params, &proofs));
+    }
+
+    #[test]
+    fn verify_batch_rejects_a_tampered_residual_in_one_proof() {
+        let n0 = 1usize << 11;
+        let builder = DeepAliRealBuilder::default();
+        let columns_per_proof = sample_columns_per_proof(n0, 3, 47);
+        let params = sample_batch_verify_params();
+
+        let mut proofs = deep_fri_prove_many(&builder, &columns_per_proof, n0, &params);
+        proofs[2].queries[0].per_layer_payloads[0].s_i += F::from(1u64);
+        assert!(!deep_fri_verify_batch(&params, &proofs));
+    }
 }
\ No newline at end of file
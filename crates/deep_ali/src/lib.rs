@@ -1,20 +1,131 @@
-use ark_ff::{Field, One, Zero};
+use ark_ff::{FftField, Field, One, Zero};
 use ark_pallas::Fr as F;
+use merkle::{MerkleChannelCfg, MerkleProof, MerkleProver as ColumnMerkleProver, MerkleTree};
+use transcript::Transcript;
+
+/// The concrete field every Merkle/transcript-backed piece of this crate
+/// (`ColumnCommitment`, `deep_ali_challenges`, `deep_ali_merge_batch`, `column_label`) is
+/// pinned to, since `merkle` and `transcript` are themselves hard-wired to Pallas's
+/// scalar field rather than generic over it. The pure `H`-arithmetic below
+/// (`DomainH`/`lagrange_eval_on_h`/`zh_at`/`deep_ali_merge_evals*`) has no such
+/// dependency and is generic over any `F: FftField` instead.
+pub type PallasFr = F;
+
+/// The FRI/DEEP-FRI low-degree test this crate's `DeepAliBuilder` quotients get checked
+/// against -- see `fri::deep_fri_prove`/`fri::deep_fri_verify`.
+pub mod fri;
 
 /// Return true if z ∈ H = <omega> of size n, i.e., z^n == 1.
-fn is_in_domain(z: F, n: usize) -> bool {
+fn is_in_domain<F: FftField>(z: F, n: usize) -> bool {
     z.pow(&[n as u64, 0, 0, 0]) == F::one()
 }
 
+/// Domain-separation labels for `deep_ali_challenges`' absorbs/squeezes, distinct from
+/// `fri.rs`'s own `ds` module since the two bind unrelated transcript steps.
+mod ds {
+    pub const ROOT_A: &[u8] = b"ALI/root/a";
+    pub const ROOT_S: &[u8] = b"ALI/root/s";
+    pub const ROOT_E: &[u8] = b"ALI/root/e";
+    pub const ROOT_T: &[u8] = b"ALI/root/t";
+    pub const ROOT_R: &[u8] = b"ALI/root/r";
+    pub const CHAL_Z: &[u8] = b"ALI/z";
+    pub const CHAL_BETA: &[u8] = b"ALI/beta";
+}
+
+/// Derives the non-interactive `(z, beta)` pair `deep_ali_merge_evals_blinded` needs,
+/// binding every committed evaluation root into `tr` first so neither challenge can be
+/// chosen (or biased) before the prover's messages are fixed. Mirrors how the FRI
+/// subsystem (`fri.rs`) hashes its layer roots before deriving query indices,
+/// replacing the raw-seed `sample_z_beta_from_seed` with a genuine Fiat-Shamir
+/// transcript. `root_r` is `None` when the caller isn't using the optional blinding
+/// column `R`. `z` is resampled (each attempt re-absorbed under its own label) until it
+/// lands outside `H`, since `deep_ali_merge_evals_blinded` requires `z ∉ H`.
+pub fn deep_ali_challenges<T: Transcript>(
+    tr: &mut T,
+    root_a: F,
+    root_s: F,
+    root_e: F,
+    root_t: F,
+    root_r: Option<F>,
+    n: usize,
+) -> (F, F) {
+    tr.absorb_bytes(ds::ROOT_A);
+    tr.absorb_root(&root_a);
+    tr.absorb_bytes(ds::ROOT_S);
+    tr.absorb_root(&root_s);
+    tr.absorb_bytes(ds::ROOT_E);
+    tr.absorb_root(&root_e);
+    tr.absorb_bytes(ds::ROOT_T);
+    tr.absorb_root(&root_t);
+    if let Some(root_r) = root_r {
+        tr.absorb_bytes(ds::ROOT_R);
+        tr.absorb_root(&root_r);
+    }
+
+    let mut z = tr.challenge(ds::CHAL_Z);
+    while is_in_domain(z, n) {
+        z = tr.challenge(ds::CHAL_Z);
+    }
+    let beta = tr.challenge(ds::CHAL_BETA);
+    (z, beta)
+}
+
+/// Domain-separating tree labels for the five DEEP-ALI columns, so committing `a_eval`
+/// and `t_eval` under the same arity can never be confused for one another.
+pub mod column_label {
+    pub const A: u64 = 1;
+    pub const S: u64 = 2;
+    pub const E: u64 = 3;
+    pub const T: u64 = 4;
+    pub const F0: u64 = 5;
+}
+
+/// A Merkle-committed DEEP-ALI evaluation column (`a_eval`/`s_eval`/`e_eval`/`t_eval`/
+/// `f0_eval`), built over a Poseidon-hashed arity-2 tree -- the same commitment
+/// primitive `fri.rs` uses for each folded FRI layer. This is the shared commit step
+/// feeding both `deep_ali_challenges` (which absorbs `root()`) and a later low-degree
+/// test's query phase over `f0_eval` (which opens authentication paths via `open`).
+pub struct ColumnCommitment {
+    cfg: MerkleChannelCfg,
+    tree: MerkleTree,
+}
+
+impl ColumnCommitment {
+    pub fn commit(values: &[F], tree_label: u64) -> Self {
+        let cfg = MerkleChannelCfg::new(2).with_tree_label(tree_label).with_leaf_ds();
+        let tree = MerkleTree::new(values.to_vec(), cfg.clone());
+        Self { cfg, tree }
+    }
+
+    pub fn root(&self) -> F {
+        self.tree.root()
+    }
+
+    pub fn open(&self, index: usize) -> MerkleProof {
+        ColumnMerkleProver::new(self.cfg.clone()).open_single(&self.tree, &[index])
+    }
+}
+
+/// Verifies a `ColumnCommitment::open` proof against `root` for the column committed
+/// under `tree_label` (one of the `column_label` constants).
+pub fn verify_column(root: F, index: usize, leaf: F, proof: &MerkleProof, tree_label: u64) -> bool {
+    let cfg = MerkleChannelCfg::new(2).with_tree_label(tree_label).with_leaf_ds();
+    ColumnMerkleProver::new(cfg).verify_single(&root, &[index], &[leaf], proof)
+}
+
 /// Vanishing polynomial on H: Z_H(z) = z^n - 1
-fn zh_at(z: F, n: usize) -> F {
+fn zh_at<F: FftField>(z: F, n: usize) -> F {
     z.pow(&[n as u64, 0, 0, 0]) - F::one()
 }
 
 /// Evaluate the unique degree < n polynomial with values = {f(ω^j)} on H at z ∉ H:
 /// Using the correct barycentric form for multiplicative subgroup H:
 /// f(z) = (Z_H(z)/n) * sum_j f(ω^j) * ω^j / (z - ω^j)
-pub fn lagrange_eval_on_h(values: &[F], z: F, omega: F) -> F {
+///
+/// Generic over any `F: FftField` (a field with a radix-2 two-adic subgroup), so the
+/// same merge/eval code can run over e.g. BLS12-381's scalar field for a KZG path and
+/// Pallas's for a FRI/Halo-style path, instead of duplicating this per field.
+pub fn lagrange_eval_on_h<F: FftField>(values: &[F], z: F, omega: F) -> F {
     let n = values.len();
     assert!(n > 0, "non-empty domain");
     if is_in_domain(z, n) {
@@ -51,7 +162,7 @@ pub fn lagrange_eval_on_h(values: &[F], z: F, omega: F) -> F {
 /// - z,
 /// - c* = Φ(z)/Z_H(z),
 /// with Φ = A·S + E − T.
-pub fn deep_ali_merge_evals(
+pub fn deep_ali_merge_evals<F: FftField>(
     a_eval: &[F],
     s_eval: &[F],
     e_eval: &[F],
@@ -65,7 +176,7 @@ pub fn deep_ali_merge_evals(
 /// DEEP-ALI merge with optional blinding term β·R(x).
 /// If r_eval_opt is Some(r_eval), Φ̃ = A·S + E − T + β·R; else Φ̃ = A·S + E − T.
 /// Returns f0 evaluations, z, c*, with f0(ω^j) = Φ̃(ω^j)/(ω^j − z) on H and c* = Φ̃(z)/Z_H(z).
-pub fn deep_ali_merge_evals_blinded(
+pub fn deep_ali_merge_evals_blinded<F: FftField>(
     a_eval: &[F],
     s_eval: &[F],
     e_eval: &[F],
@@ -112,16 +223,58 @@ pub fn deep_ali_merge_evals_blinded(
     (f0_eval, z, c_star)
 }
 
-/// Lightweight domain cache for H = <omega> (radix-2).
+/// Batches several column openings at one out-of-domain point `z` into a single DEEP
+/// quotient: `Ψ(x) = Σ_k coeffs[k]·columns[k](x)` on `H`, `Ψ(z)` via `lagrange_eval_on_h`,
+/// and `f0(ω^j) = (Ψ(ω^j) − Ψ(z))/(ω^j − z)`. Unlike `deep_ali_merge_evals_blinded`'s
+/// fixed `Φ = A·S + E − T` (a product of columns), this takes an arbitrary number of
+/// columns combined only linearly -- `coeffs` are typically successive powers of a
+/// verifier challenge, as in halo2's multiopen construction, but are supplied by the
+/// caller rather than derived here so batching stays independent of any particular
+/// challenge-derivation scheme. Returns `(f0_eval, psi_z)`: the verifier recomputes
+/// `Ψ(z)` the same way and needs only one low-degree check on `f0_eval`, no matter how
+/// many columns were opened.
+pub fn deep_ali_merge_batch(columns: &[&[F]], coeffs: &[F], z: F, omega: F) -> (Vec<F>, F) {
+    assert!(!columns.is_empty(), "at least one column required");
+    assert_eq!(columns.len(), coeffs.len(), "one coefficient per column");
+    let n = columns[0].len();
+    assert!(n > 1);
+    assert!(columns.iter().all(|c| c.len() == n), "all columns must share one length");
+    assert!(!is_in_domain(z, n), "z must be outside H");
+
+    // Ψ on H
+    let mut psi_eval = vec![F::zero(); n];
+    for (&col, &coeff) in columns.iter().zip(coeffs.iter()) {
+        for i in 0..n {
+            psi_eval[i] += coeff * col[i];
+        }
+    }
+
+    // Ψ(z) via Lagrange
+    let psi_z = lagrange_eval_on_h(&psi_eval, z, omega);
+
+    // f0 on H: f0(ω^j) = (Ψ(ω^j) − Ψ(z)) / (ω^j − z)
+    let mut f0_eval = vec![F::zero(); n];
+    let mut omega_j = F::one();
+    for j in 0..n {
+        f0_eval[j] = (psi_eval[j] - psi_z) * (omega_j - z).inverse().expect("z ∉ H");
+        omega_j *= omega;
+    }
+
+    (f0_eval, psi_z)
+}
+
+/// Lightweight domain cache for H = <omega> (radix-2), generic over any `F: FftField`
+/// so the same cache/merge code serves every field this crate's callers pick, while
+/// defaulting to [`PallasFr`] for every pre-existing unparameterized `DomainH` use.
 /// Caches omega^j to reduce repeated work across evaluations/merges.
 #[derive(Clone)]
-pub struct DomainH {
+pub struct DomainH<F: FftField = PallasFr> {
     pub n: usize,
     pub omega: F,
     pub omega_pows: Vec<F>, // [1, ω, ω^2, ..., ω^{n-1}]
 }
 
-impl DomainH {
+impl<F: FftField> DomainH<F> {
     /// Construct a radix-2 domain of size n and cache ω and its powers.
     pub fn new_radix2(n: usize) -> Self {
         use ark_poly::domain::radix2::Radix2EvaluationDomain as Domain;
@@ -232,6 +385,77 @@ impl DomainH {
 
         (f0_eval, z, c_star)
     }
+
+    /// Builds the size-`blowup·n` coset domain `{ shift · ω_ext^j }` over the enlarged
+    /// radix-2 domain of size `blowup·n`, used to extend evaluations given on this `H`
+    /// (size `n`) into the larger Reed-Solomon codeword FRI runs its folding low-degree
+    /// test against.
+    pub fn new_coset_radix2(n: usize, blowup: usize, shift: F) -> CosetDomainH<F> {
+        use ark_poly::domain::radix2::Radix2EvaluationDomain as Domain;
+        use ark_poly::EvaluationDomain;
+
+        assert!(
+            blowup.is_power_of_two() && blowup > 0,
+            "blowup factor must be a power of two"
+        );
+        assert!(!shift.is_zero(), "coset shift must be nonzero");
+
+        let coset_size = n * blowup;
+        let ext_domain = Domain::<F>::new(coset_size).expect("radix-2 domain exists for this size");
+
+        CosetDomainH {
+            base_n: n,
+            coset_size,
+            shift,
+            ext_omega: ext_domain.group_gen,
+        }
+    }
+}
+
+/// A coset Reed-Solomon domain `{ shift · ω_ext^j : j = 0..coset_size }` over the
+/// enlarged radix-2 domain, mirroring the extended-Lagrange/coset representation used in
+/// halo2's evaluation-domain layer: the prerequisite for running FRI on a codeword
+/// originally defined by values on the plain subgroup `H` that `DomainH` caches.
+#[derive(Clone)]
+pub struct CosetDomainH<F: FftField = PallasFr> {
+    pub base_n: usize,
+    pub coset_size: usize,
+    pub shift: F,
+    pub ext_omega: F,
+}
+
+impl<F: FftField> CosetDomainH<F> {
+    /// Interpolates the degree-`< base_n` polynomial defined by `values` on `H`, then
+    /// re-evaluates it on this coset via an inverse FFT over `H` followed by a forward
+    /// FFT over the shifted enlarged domain.
+    pub fn low_degree_extend(&self, values: &[F]) -> Vec<F> {
+        use ark_poly::domain::radix2::Radix2EvaluationDomain as Domain;
+        use ark_poly::EvaluationDomain;
+
+        assert_eq!(values.len(), self.base_n, "values length must equal H's size");
+
+        let base_domain = Domain::<F>::new(self.base_n).expect("radix-2 domain exists for this size");
+        let mut coeffs = values.to_vec();
+        base_domain.ifft_in_place(&mut coeffs);
+        coeffs.resize(self.coset_size, F::zero());
+
+        let mut pow = F::one();
+        for c in coeffs.iter_mut() {
+            *c *= pow;
+            pow *= self.shift;
+        }
+
+        let ext_domain = Domain::<F>::new(self.coset_size).expect("radix-2 domain exists for this size");
+        ext_domain.fft_in_place(&mut coeffs);
+        coeffs
+    }
+
+    /// Vanishing polynomial of the original `H` (size `base_n`), evaluated at a coset
+    /// point `z = shift · ω_ext^j`: still `z^base_n − 1`, since `Z_H` depends only on `H`,
+    /// not on the larger domain its coset points are drawn from.
+    pub fn zh_at(&self, z: F) -> F {
+        zh_at(z, self.base_n)
+    }
 }
 
 /// Deterministic “simulatable view” sampling for tests:
@@ -558,4 +782,154 @@ mod tests {
         assert_ne!(z1, z3);
         // b3 can collide by chance; we only require determinism for a fixed seed.
     }
+
+    #[test]
+    fn low_degree_extend_matches_direct_evaluation_on_the_coset() {
+        let n = 8usize;
+        let blowup = 4usize;
+        let domain = DomainH::new_radix2(n);
+
+        let coeffs: Vec<F> = (0..n).map(|i| F::from((i as u64) + 1)).collect();
+        let values = eval_on_domain(&coeffs, domain.omega, n);
+
+        let shift = F::from(5u64);
+        let coset = DomainH::new_coset_radix2(n, blowup, shift);
+        assert_eq!(coset.coset_size, n * blowup);
+
+        let evals = coset.low_degree_extend(&values);
+        assert_eq!(evals.len(), n * blowup);
+
+        for (j, &e) in evals.iter().enumerate() {
+            let x = shift * coset.ext_omega.pow(&[j as u64, 0, 0, 0]);
+            assert_eq!(e, poly_eval(&coeffs, x));
+        }
+    }
+
+    #[test]
+    fn coset_zh_at_is_the_base_domain_vanishing_polynomial() {
+        let n = 16usize;
+        let coset = DomainH::new_coset_radix2(n, 2usize, F::from(3u64));
+        let z = F::from(999u64);
+        assert_eq!(coset.zh_at(z), z.pow(&[n as u64, 0, 0, 0]) - F::one());
+    }
+
+    #[test]
+    #[should_panic(expected = "power of two")]
+    fn new_coset_radix2_rejects_non_power_of_two_blowup() {
+        let _ = DomainH::new_coset_radix2(8usize, 3usize, F::from(5u64));
+    }
+
+    #[test]
+    fn deep_ali_challenges_is_deterministic_and_z_lands_outside_h() {
+        use transcript::{default_params, PoseidonTranscript};
+
+        let n = 64usize;
+        let roots = (F::from(1u64), F::from(2u64), F::from(3u64), F::from(4u64));
+
+        let mut tr1 = PoseidonTranscript::new(b"DEEP-ALI-CHAL-TEST", default_params());
+        let (z1, beta1) =
+            deep_ali_challenges(&mut tr1, roots.0, roots.1, roots.2, roots.3, None, n);
+
+        let mut tr2 = PoseidonTranscript::new(b"DEEP-ALI-CHAL-TEST", default_params());
+        let (z2, beta2) =
+            deep_ali_challenges(&mut tr2, roots.0, roots.1, roots.2, roots.3, None, n);
+
+        assert_eq!(z1, z2);
+        assert_eq!(beta1, beta2);
+        assert!(!is_in_domain(z1, n));
+    }
+
+    #[test]
+    fn deep_ali_challenges_is_sensitive_to_the_blinding_root() {
+        use transcript::{default_params, PoseidonTranscript};
+
+        let n = 64usize;
+        let mut tr_without_r = PoseidonTranscript::new(b"DEEP-ALI-CHAL-TEST-R", default_params());
+        let (z_without_r, _) = deep_ali_challenges(
+            &mut tr_without_r,
+            F::from(1u64),
+            F::from(2u64),
+            F::from(3u64),
+            F::from(4u64),
+            None,
+            n,
+        );
+
+        let mut tr_with_r = PoseidonTranscript::new(b"DEEP-ALI-CHAL-TEST-R", default_params());
+        let (z_with_r, _) = deep_ali_challenges(
+            &mut tr_with_r,
+            F::from(1u64),
+            F::from(2u64),
+            F::from(3u64),
+            F::from(4u64),
+            Some(F::from(42u64)),
+            n,
+        );
+
+        assert_ne!(z_without_r, z_with_r);
+    }
+
+    #[test]
+    fn column_commitment_open_and_verify_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let values: Vec<F> = (0..32).map(|_| F::rand(&mut rng)).collect();
+
+        let commitment = ColumnCommitment::commit(&values, column_label::A);
+        let root = commitment.root();
+
+        let idx = 17usize;
+        let proof = commitment.open(idx);
+        assert!(verify_column(root, idx, values[idx], &proof, column_label::A));
+    }
+
+    #[test]
+    fn column_commitment_rejects_a_leaf_from_the_wrong_column_label() {
+        let mut rng = StdRng::seed_from_u64(12);
+        let values: Vec<F> = (0..32).map(|_| F::rand(&mut rng)).collect();
+
+        let commitment = ColumnCommitment::commit(&values, column_label::S);
+        let root = commitment.root();
+
+        let idx = 4usize;
+        let proof = commitment.open(idx);
+        // Same root/leaf/proof bytes, but verified under a different column's label.
+        assert!(!verify_column(root, idx, values[idx], &proof, column_label::T));
+    }
+
+    #[test]
+    fn deep_ali_merge_batch_identity_holds_on_h() {
+        let n = 16usize;
+        let omega = find_primitive_root_pow2(n);
+        let mut rng = StdRng::seed_from_u64(21);
+
+        let col_a: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+        let col_b: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+        let col_c: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+        let columns: Vec<&[F]> = vec![&col_a, &col_b, &col_c];
+
+        let x4 = F::from(13u64);
+        let coeffs = vec![F::one(), x4, x4 * x4];
+
+        let (z, _beta) = sample_z_beta_from_seed(99, n);
+        let (f0_eval, psi_z) = deep_ali_merge_batch(&columns, &coeffs, z, omega);
+
+        // f0(ω^j)(ω^j − z) + Ψ(z) == Σ_k coeffs[k]·columns[k](ω^j)
+        let mut omega_j = F::one();
+        for j in 0..n {
+            let lhs = f0_eval[j] * (omega_j - z) + psi_z;
+            let rhs = coeffs[0] * col_a[j] + coeffs[1] * col_b[j] + coeffs[2] * col_c[j];
+            assert_eq!(lhs, rhs);
+            omega_j *= omega;
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "one coefficient per column")]
+    fn deep_ali_merge_batch_rejects_mismatched_coeffs_len() {
+        let n = 8usize;
+        let omega = find_primitive_root_pow2(n);
+        let col: Vec<F> = vec![F::zero(); n];
+        let (z, _) = sample_z_beta_from_seed(1, n);
+        let _ = deep_ali_merge_batch(&[&col], &[F::one(), F::one()], z, omega);
+    }
 }
@@ -1,4 +1,5 @@
 use ark_bls12_381::Fr as F;
+use ark_ff::{FftField, Field, One, Zero};
 use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
 
 /// Perform IFFT in place on a slice by copying through a Vec.
@@ -31,6 +32,94 @@ pub fn ifft(domain: &Radix2EvaluationDomain<F>, evals: &[F]) -> Vec<F> {
     v
 }
 
+/// The field's multiplicative generator, used as the default coset offset: it lies
+/// outside every `Radix2EvaluationDomain` subgroup we build, so `offset * w^i` never
+/// collides with a root of unity.
+pub fn default_coset_offset() -> F {
+    F::GENERATOR
+}
+
+/// Evaluate `coeffs` over the coset `{ offset * w^i }` of `domain` instead of the
+/// subgroup `{ w^i }`, by scaling coefficient `i` by `offset^i` before the ordinary
+/// forward FFT. `coeffs` must already be padded to `domain.size()`.
+pub fn coset_fft(domain: &Radix2EvaluationDomain<F>, offset: F, coeffs: &[F]) -> Vec<F> {
+    let mut scaled = coeffs.to_vec();
+    let mut pow = F::one();
+    for c in scaled.iter_mut() {
+        *c *= pow;
+        pow *= offset;
+    }
+    fft(domain, &scaled)
+}
+
+/// Inverse of `coset_fft`: an ordinary IFFT followed by dividing coefficient `i` by
+/// `offset^i` to undo the coset scaling.
+pub fn coset_ifft(domain: &Radix2EvaluationDomain<F>, offset: F, evals: &[F]) -> Vec<F> {
+    let mut coeffs = ifft(domain, evals);
+    let offset_inv = offset.inverse().expect("coset offset must be nonzero");
+    let mut pow = F::one();
+    for c in coeffs.iter_mut() {
+        *c *= pow;
+        pow *= offset_inv;
+    }
+    coeffs
+}
+
+/// Low-degree extension: zero-pads `coeffs` (length `n`, a power of two) to
+/// `n * blowup` and evaluates over a coset of the enlarged domain, so the evaluations
+/// never land on a root of unity of that domain -- the standard STARK trace LDE
+/// performed before committing to the trace with the Merkle layer.
+pub struct Lde {
+    pub domain: Radix2EvaluationDomain<F>,
+    pub evals: Vec<F>,
+}
+
+pub fn lde(coeffs: &[F], blowup: usize) -> Lde {
+    assert!(blowup.is_power_of_two() && blowup > 0, "blowup factor must be a power of two");
+    let enlarged_size = coeffs.len() * blowup;
+    let domain = Radix2EvaluationDomain::<F>::new(enlarged_size).expect("lde domain");
+    let mut padded = coeffs.to_vec();
+    padded.resize(enlarged_size, F::zero());
+    let evals = coset_fft(&domain, default_coset_offset(), &padded);
+    Lde { domain, evals }
+}
+
+/// Interpolate evaluation-form `evals` into coefficients -- a thin, more
+/// descriptively-named wrapper over `ifft`, mirroring halo2's `lagrange_interpolate`.
+pub fn interpolate(domain: &Radix2EvaluationDomain<F>, evals: &[F]) -> Vec<F> {
+    ifft(domain, evals)
+}
+
+/// Evaluate the interpolant of `evals` at an out-of-domain point `z`, via the
+/// subgroup barycentric formula, without materializing coefficients:
+/// `f(z) = (Z_H(z)/n) * sum_i evals[i] * w^i / (z - w^i)`, where `Z_H(z) = z^n - 1`.
+/// If `z` coincides with a domain point `w^i`, returns `evals[i]` directly (the
+/// formula's denominator vanishes there).
+pub fn eval_barycentric(domain: &Radix2EvaluationDomain<F>, evals: &[F], z: F) -> F {
+    let n = domain.size();
+    assert_eq!(evals.len(), n, "evals must match the domain's size");
+
+    let mut w_i = F::one();
+    for &v in evals {
+        if z == w_i {
+            return v;
+        }
+        w_i *= domain.group_gen;
+    }
+
+    let z_h = z.pow([n as u64]) - F::one();
+    let factor = z_h * domain.size_inv;
+
+    let mut w_i = F::one();
+    let mut acc = F::zero();
+    for &v in evals {
+        let denom = z - w_i;
+        acc += v * w_i * denom.inverse().expect("checked z != w^i above");
+        w_i *= domain.group_gen;
+    }
+    factor * acc
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -52,4 +141,93 @@ mod tests {
         ifft_in_place(&domain, &mut coeffs[..]);
         assert_eq!(coeffs, vec![F::one(); n]);
     }
+
+    #[test]
+    fn coset_fft_ifft_roundtrip() {
+        let n = 8usize;
+        let domain = Radix2EvaluationDomain::<F>::new(n).expect("domain");
+        let coeffs: Vec<F> = (0..n).map(|i| F::from((i as u64) + 1)).collect();
+        let offset = default_coset_offset();
+
+        let evals = coset_fft(&domain, offset, &coeffs);
+        let back = coset_ifft(&domain, offset, &evals);
+        assert_eq!(coeffs, back);
+    }
+
+    #[test]
+    fn coset_fft_matches_direct_evaluation_off_the_subgroup() {
+        let n = 4usize;
+        let domain = Radix2EvaluationDomain::<F>::new(n).expect("domain");
+        let coeffs: Vec<F> = vec![F::from(3u64), F::from(5u64), F::from(7u64), F::from(11u64)];
+        let offset = default_coset_offset();
+
+        let evals = coset_fft(&domain, offset, &coeffs);
+        for (i, &eval) in evals.iter().enumerate() {
+            let x = offset * domain.group_gen.pow([i as u64]);
+            let mut acc = F::zero();
+            for &c in coeffs.iter().rev() {
+                acc = acc * x + c;
+            }
+            assert_eq!(eval, acc);
+            // The coset point is never a root of unity of this domain.
+            assert_ne!(x.pow([n as u64]), F::one());
+        }
+    }
+
+    #[test]
+    fn lde_enlarges_the_domain_and_preserves_the_polynomial() {
+        let n = 4usize;
+        let blowup = 4usize;
+        let coeffs: Vec<F> = vec![F::from(1u64), F::from(2u64), F::from(3u64), F::from(4u64)];
+
+        let result = lde(&coeffs, blowup);
+        assert_eq!(result.domain.size(), n * blowup);
+        assert_eq!(result.evals.len(), n * blowup);
+
+        for (i, &eval) in result.evals.iter().enumerate() {
+            let x = default_coset_offset() * result.domain.group_gen.pow([i as u64]);
+            let mut acc = F::zero();
+            for &c in coeffs.iter().rev() {
+                acc = acc * x + c;
+            }
+            assert_eq!(eval, acc);
+        }
+    }
+
+    #[test]
+    fn interpolate_is_the_inverse_of_fft() {
+        let n = 8usize;
+        let domain = Radix2EvaluationDomain::<F>::new(n).expect("domain");
+        let coeffs: Vec<F> = (0..n).map(|i| F::from(i as u64)).collect();
+
+        let evals = fft(&domain, &coeffs);
+        assert_eq!(interpolate(&domain, &evals), coeffs);
+    }
+
+    #[test]
+    fn eval_barycentric_matches_direct_evaluation_off_the_subgroup() {
+        let n = 8usize;
+        let domain = Radix2EvaluationDomain::<F>::new(n).expect("domain");
+        let coeffs: Vec<F> = (0..n).map(|i| F::from((i as u64) + 1)).collect();
+        let evals = fft(&domain, &coeffs);
+
+        let z = F::from(999u64);
+        let mut direct = F::zero();
+        for &c in coeffs.iter().rev() {
+            direct = direct * z + c;
+        }
+        assert_eq!(eval_barycentric(&domain, &evals, z), direct);
+    }
+
+    #[test]
+    fn eval_barycentric_on_a_domain_point_returns_the_eval_directly() {
+        let n = 8usize;
+        let domain = Radix2EvaluationDomain::<F>::new(n).expect("domain");
+        let evals: Vec<F> = (0..n).map(|i| F::from((i as u64) * 3 + 1)).collect();
+
+        for (i, &v) in evals.iter().enumerate() {
+            let z = domain.group_gen.pow([i as u64]);
+            assert_eq!(eval_barycentric(&domain, &evals, z), v);
+        }
+    }
 }
@@ -0,0 +1,43 @@
+use ark_ff::UniformRand;
+use ark_pallas::Fr as F;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use field::Domain;
+use rand::{rngs::StdRng, SeedableRng};
+
+// Serial vs `parallel`-feature transforms at the sizes where large-trace LDE starts
+// to dominate prover time (N = 2^16..2^22).
+fn bench_ntt_and_coset_lde(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ntt");
+    let rng_seed = 7u64;
+
+    for log_n in 16..=22 {
+        let n = 1usize << log_n;
+        let domain = Domain::new(log_n).expect("root of unity must exist");
+
+        group.bench_with_input(BenchmarkId::new("ntt", n), &n, |b, _| {
+            b.iter_batched(
+                || {
+                    let mut rng = StdRng::seed_from_u64(rng_seed);
+                    (0..n).map(|_| F::rand(&mut rng)).collect::<Vec<F>>()
+                },
+                |mut coeffs| domain.ntt(&mut coeffs).expect("coeffs match the domain's size"),
+                BatchSize::LargeInput,
+            );
+        });
+
+        group.bench_with_input(BenchmarkId::new("coset_lde_blowup4", n), &n, |b, _| {
+            b.iter_batched(
+                || {
+                    let mut rng = StdRng::seed_from_u64(rng_seed + 1);
+                    (0..n).map(|_| F::rand(&mut rng)).collect::<Vec<F>>()
+                },
+                |coeffs| domain.coset_lde(&coeffs, 4),
+                BatchSize::LargeInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(ntt_benches, bench_ntt_and_coset_lde);
+criterion_main!(ntt_benches);
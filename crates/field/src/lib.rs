@@ -17,6 +17,19 @@ use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate
 #[cfg(feature = "serde1")]
 use serde::{Deserialize, Serialize};
 
+// Optional rayon-backed path for the NTT butterfly rounds and coset scaling below
+// (mirrors bellman's `multicore::Worker` split): each block/chunk is independent of
+// every other within a round, so running them concurrently is bit-for-bit identical
+// to the sequential version -- only the iteration strategy changes. Single-threaded
+// builds (the default) are unaffected.
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Domain size above which `ntt`/`intt`/`coset_lde` switch to the `parallel`-feature
+/// rayon path; below it, per-thread dispatch overhead outweighs the butterfly work.
+#[cfg(feature = "parallel")]
+const PARALLEL_THRESHOLD: usize = 1 << 15;
+
 /// A simple multiplicative subgroup domain of size n = 2^log_n.
 /// Stores the generator (omega) and optionally precomputes the elements.
 ///
@@ -33,10 +46,21 @@ pub struct Domain {
     pub log_n: usize,
     /// A primitive n-th root of unity in the field
     pub omega: F,
+    /// omega^{-1}, precomputed for `intt` (analogous to bellman's `omegainv`)
+    pub omega_inv: F,
+    /// 1/size, precomputed for `intt`'s final scaling (analogous to bellman's `minv`)
+    pub n_inv: F,
     /// Optional cache of domain elements [1, omega, omega^2, ..., omega^{n-1}]
     pub elements: Vec<F>,
 }
 
+/// The only way `Domain::ntt`/`Domain::intt` can fail: the input isn't exactly the
+/// domain's size, so there's no well-defined transform to run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NttError {
+    LengthMismatch { expected: usize, got: usize },
+}
+
 impl Domain {
     /// Construct a domain of size n = 2^log_n, returning None if such a root of unity
     /// does not exist in the field (should exist for Fr for reasonable sizes).
@@ -44,10 +68,16 @@ impl Domain {
         let size = 1usize << log_n;
         // F::get_root_of_unity expects the size (power-of-two).
         let omega = F::get_root_of_unity(size as u64)?;
+        let omega_inv = omega.inverse().expect("a root of unity is never zero");
+        let n_inv = F::from(size as u64)
+            .inverse()
+            .expect("domain size is nonzero in the field");
         Some(Self {
             size,
             log_n,
             omega,
+            omega_inv,
+            n_inv,
             elements: Vec::new(),
         })
     }
@@ -90,6 +120,240 @@ impl Domain {
             n: self.size,
         }
     }
+
+    /// In-place forward NTT: coefficient form -> evaluation form on this domain,
+    /// via an iterative Cooley-Tukey butterfly (bellman's `EvaluationDomain::fft`).
+    pub fn ntt(&self, coeffs: &mut [F]) -> Result<(), NttError> {
+        if coeffs.len() != self.size {
+            return Err(NttError::LengthMismatch { expected: self.size, got: coeffs.len() });
+        }
+        bitreverse_permute(coeffs, self.log_n);
+        run_butterfly(coeffs, self.omega, self.log_n);
+        Ok(())
+    }
+
+    /// In-place inverse NTT: evaluation form -> coefficient form. Same butterfly as
+    /// `ntt`, run with `omega_inv`, followed by the `n_inv` scaling every inverse
+    /// transform needs.
+    pub fn intt(&self, evals: &mut [F]) -> Result<(), NttError> {
+        if evals.len() != self.size {
+            return Err(NttError::LengthMismatch { expected: self.size, got: evals.len() });
+        }
+        bitreverse_permute(evals, self.log_n);
+        run_butterfly(evals, self.omega_inv, self.log_n);
+        for v in evals.iter_mut() {
+            *v *= self.n_inv;
+        }
+        Ok(())
+    }
+
+    /// Low-degree-extend `coeffs` (length `self.size`) onto a coset of the
+    /// `self.size * blowup`-sized enlarged domain -- the blown-up trace LDE every
+    /// STARK prover needs (Winterfell exposes exactly `blowup_factor` as a proof
+    /// option). Shorthand for `CosetDomain::new(self, blowup).lde(coeffs)`; callers
+    /// extending more than one polynomial at the same blowup should build the
+    /// `CosetDomain` once and reuse it instead.
+    pub fn coset_lde(&self, coeffs: &[F], blowup: usize) -> Vec<F> {
+        CosetDomain::new(self, blowup).lde(coeffs)
+    }
+
+    /// Inverse of `coset_lde`: recovers the original `self.size` coefficients from
+    /// evaluations on the coset of the `self.size * blowup`-sized enlarged domain.
+    pub fn coset_intt(&self, evals: &[F], blowup: usize) -> Vec<F> {
+        CosetDomain::new(self, blowup).intt(evals)
+    }
+
+    /// Interpolate evaluation-form `evals` (length `self.size`, indexed by
+    /// `self.element(i)`) into polynomial coefficients -- the natural inverse NTT
+    /// over this domain, named to mirror halo2's `lagrange_interpolate`.
+    pub fn interpolate(&self, evals: &[F]) -> Vec<F> {
+        let mut coeffs = evals.to_vec();
+        self.intt(&mut coeffs).expect("evals must match the domain's size");
+        coeffs
+    }
+
+    /// Evaluate the interpolant of `evals` at an out-of-domain point `z`, via the
+    /// subgroup barycentric formula, without materializing coefficients:
+    /// `f(z) = (Z_H(z)/n) * sum_i evals[i] * omega^i / (z - omega^i)`, where
+    /// `Z_H(z) = z^n - 1`. If `z` coincides with a domain point `omega^i`, returns
+    /// `evals[i]` directly (the formula's denominator vanishes there).
+    pub fn eval_barycentric(&self, evals: &[F], z: F) -> F {
+        assert_eq!(evals.len(), self.size, "evals must match the domain's size");
+
+        let mut omega_i = F::one();
+        for &v in evals {
+            if z == omega_i {
+                return v;
+            }
+            omega_i *= self.omega;
+        }
+
+        let z_h = z.pow([self.size as u64]) - F::one();
+        let factor = z_h * self.n_inv;
+
+        let mut omega_i = F::one();
+        let mut acc = F::zero();
+        for &v in evals {
+            let denom = z - omega_i;
+            acc += v * omega_i * denom.inverse().expect("checked z != omega^i above");
+            omega_i *= self.omega;
+        }
+        factor * acc
+    }
+}
+
+/// Distributes powers of `base` across `vals`, i.e. `vals[i] *= base^i` -- the "distribute
+/// powers" step bellman's coset FFT uses before an ordinary transform to shift evaluation
+/// onto a coset instead of the subgroup itself. Chunked across a rayon thread pool under
+/// the `parallel` feature once `vals` is large enough to be worth the dispatch.
+fn distribute_powers(vals: &mut [F], base: F) {
+    #[cfg(feature = "parallel")]
+    if vals.len() >= PARALLEL_THRESHOLD {
+        distribute_powers_parallel(vals, base);
+        return;
+    }
+    distribute_powers_serial(vals, base);
+}
+
+fn distribute_powers_serial(vals: &mut [F], base: F) {
+    let mut pow = F::one();
+    for v in vals.iter_mut() {
+        *v *= pow;
+        pow *= base;
+    }
+}
+
+#[cfg(feature = "parallel")]
+fn distribute_powers_parallel(vals: &mut [F], base: F) {
+    const CHUNK: usize = 1 << 12;
+    vals.par_chunks_mut(CHUNK).enumerate().for_each(|(ci, chunk)| {
+        let mut pow = base.pow([(ci * CHUNK) as u64]);
+        for v in chunk.iter_mut() {
+            *v *= pow;
+            pow *= base;
+        }
+    });
+}
+
+/// A coset `g · H'` of a larger domain `H'` (size `base.size * blowup`), bundling the
+/// enlarged `Domain` together with the coset offset `g = F::GENERATOR` and its inverse
+/// so `intt` can recover the original polynomial without recomputing either.
+#[derive(Clone, Debug)]
+pub struct CosetDomain {
+    pub domain: Domain,
+    pub base_size: usize,
+    pub offset: F,
+    pub offset_inv: F,
+}
+
+impl CosetDomain {
+    /// Builds the `base.size * blowup`-sized enlarged domain and its coset offset.
+    pub fn new(base: &Domain, blowup: usize) -> Self {
+        assert!(blowup.is_power_of_two() && blowup > 0, "blowup factor must be a power of two");
+        let enlarged_log_n = base.log_n + blowup.trailing_zeros() as usize;
+        let domain = Domain::new(enlarged_log_n).expect("enlarged domain's root of unity must exist");
+        let offset = F::GENERATOR;
+        let offset_inv = offset.inverse().expect("the multiplicative generator is never zero");
+        Self { domain, base_size: base.size, offset, offset_inv }
+    }
+
+    /// Zero-pads `coeffs` (length `base_size`) up to the enlarged domain's size,
+    /// distributes powers of the coset offset across it, then runs the forward NTT.
+    pub fn lde(&self, coeffs: &[F]) -> Vec<F> {
+        assert_eq!(coeffs.len(), self.base_size, "coeffs must match the base domain's size");
+        let mut padded = coeffs.to_vec();
+        padded.resize(self.domain.size, F::zero());
+        distribute_powers(&mut padded, self.offset);
+        self.domain
+            .ntt(&mut padded)
+            .expect("padded buffer matches the enlarged domain's size by construction");
+        padded
+    }
+
+    /// Inverse of `lde`: an ordinary INTT followed by undoing the offset distribution,
+    /// truncated back down to the original `base_size` coefficients.
+    pub fn intt(&self, evals: &[F]) -> Vec<F> {
+        assert_eq!(evals.len(), self.domain.size, "evals must match the enlarged domain's size");
+        let mut coeffs = evals.to_vec();
+        self.domain
+            .intt(&mut coeffs)
+            .expect("evals already match the enlarged domain's size");
+        distribute_powers(&mut coeffs, self.offset_inv);
+        coeffs.truncate(self.base_size);
+        coeffs
+    }
+}
+
+/// Reorders `a` into bit-reversed index order, the standard prelude to an
+/// iterative (non-recursive) Cooley-Tukey butterfly.
+fn bitreverse_permute<T>(a: &mut [T], log_n: usize) {
+    let n = a.len();
+    for k in 0..n {
+        let rk = bitreverse(k, log_n);
+        if k < rk {
+            a.swap(k, rk);
+        }
+    }
+}
+
+fn bitreverse(mut n: usize, l: usize) -> usize {
+    let mut r = 0usize;
+    for _ in 0..l {
+        r = (r << 1) | (n & 1);
+        n >>= 1;
+    }
+    r
+}
+
+/// The butterfly rounds themselves, shared by `ntt` and `intt`: round `s` (m = 2^s)
+/// combines pairs `m` apart within each `2m`-sized block using the twiddle
+/// `root^(n / 2m)`, i.e. `root^(n / 2^(s+1))`.
+/// `a` must already be in bit-reversed order. Each round's `2m`-sized blocks are
+/// independent of one another, so above `PARALLEL_THRESHOLD` they're run across a
+/// rayon thread pool under the `parallel` feature instead of serially.
+fn run_butterfly(a: &mut [F], root: F, log_n: usize) {
+    let n = a.len();
+    let mut m = 1usize;
+    for _ in 0..log_n {
+        let w_m = root.pow([(n / (2 * m)) as u64]);
+        #[cfg(feature = "parallel")]
+        if n >= PARALLEL_THRESHOLD {
+            run_butterfly_round_parallel(a, w_m, m);
+            m *= 2;
+            continue;
+        }
+        run_butterfly_round_serial(a, w_m, m);
+        m *= 2;
+    }
+}
+
+/// One butterfly round over a single `2m`-sized block starting at `block[0..2m)`,
+/// shared by the serial and parallel round drivers.
+fn butterfly_block(block: &mut [F], w_m: F, m: usize) {
+    let mut w = F::one();
+    for j in 0..m {
+        let mut t = block[j + m];
+        t *= w;
+        let mut tmp = block[j];
+        tmp -= t;
+        block[j + m] = tmp;
+        block[j] += t;
+        w *= w_m;
+    }
+}
+
+fn run_butterfly_round_serial(a: &mut [F], w_m: F, m: usize) {
+    let n = a.len();
+    let mut k = 0usize;
+    while k < n {
+        butterfly_block(&mut a[k..k + 2 * m], w_m, m);
+        k += 2 * m;
+    }
+}
+
+#[cfg(feature = "parallel")]
+fn run_butterfly_round_parallel(a: &mut [F], w_m: F, m: usize) {
+    a.par_chunks_mut(2 * m).for_each(|block| butterfly_block(block, w_m, m));
 }
 
 /// Iterator over [1, omega, omega^2, ..., omega^{n-1}]
@@ -132,6 +396,222 @@ pub fn compute_powers(base: F, n: usize) -> Vec<F> {
     v
 }
 
+/// A two-adic field backend: one with a multiplicative subgroup of order `2^TWO_ADICITY`,
+/// large enough to hold every domain a DEEP-FRI layer schedule needs.
+///
+/// `deep_ali::fri` hardcodes `F` (Pallas's scalar field) throughout; this trait is the
+/// seam that lets its domain construction and per-layer fold run over a different
+/// backend -- in particular a small, fast field like [`goldilocks::Goldilocks`] --
+/// without touching the Merkle/Poseidon side of the pipeline (which stays Pallas-only
+/// until a [`merkle::MerkleHasher`] exists for that backend too; see the doc comment
+/// on `deep_ali::fri::FriDomain` for what that would take).
+pub trait Fft2AdicField:
+    Copy
+    + Clone
+    + PartialEq
+    + std::fmt::Debug
+    + std::ops::Add<Output = Self>
+    + std::ops::Sub<Output = Self>
+    + std::ops::Mul<Output = Self>
+    + std::ops::Neg<Output = Self>
+{
+    /// The largest `S` such that `2^S` divides `modulus - 1`.
+    const TWO_ADICITY: usize;
+
+    fn zero() -> Self;
+    fn one() -> Self;
+
+    /// A generator of the (unique) order-`2^TWO_ADICITY` multiplicative subgroup.
+    fn two_adic_generator() -> Self;
+
+    fn pow_u64(self, exp: u64) -> Self;
+
+    /// `omega = g^(2^(S-k))`: a generator of the order-`2^k` subgroup, `k <= S`.
+    /// Panics if `k` exceeds this field's two-adicity.
+    fn root_of_unity(k: usize) -> Self {
+        assert!(
+            k <= Self::TWO_ADICITY,
+            "order 2^{k} subgroup exceeds this field's two-adicity 2^{}",
+            Self::TWO_ADICITY
+        );
+        Self::two_adic_generator().pow_u64(1u64 << (Self::TWO_ADICITY - k))
+    }
+}
+
+impl Fft2AdicField for F {
+    const TWO_ADICITY: usize = <F as FftField>::TWO_ADICITY as usize;
+
+    fn zero() -> Self {
+        <F as Zero>::zero()
+    }
+
+    fn one() -> Self {
+        <F as One>::one()
+    }
+
+    fn two_adic_generator() -> Self {
+        <F as FftField>::TWO_ADIC_ROOT_OF_UNITY
+    }
+
+    fn pow_u64(self, exp: u64) -> Self {
+        self.pow(&[exp])
+    }
+}
+
+/// The per-layer FRI fold, generic over any [`Fft2AdicField`] backend: given a
+/// codeword's even/odd halves already evaluated at `y = x^2` (`f_even(y)`, `f_odd(y)`,
+/// the two halves of `f(x) = f_even(x^2) + x * f_odd(x^2)`), combine them with the
+/// verifier's challenge `beta` into the next layer's value `f'(y) = f_even(y) + beta *
+/// f_odd(y)`. `deep_ali::fri::fri_fold_layer` folds by an arbitrary arity `m` via an
+/// RLC of `m` buckets rather than this textbook binary split; this is the `m = 2`
+/// special case spelled out structurally, so a non-Pallas backend can reuse it.
+pub fn fft2adic_fold_pair<Fld: Fft2AdicField>(f_even_y: Fld, f_odd_y: Fld, beta: Fld) -> Fld {
+    f_even_y + beta * f_odd_y
+}
+
+/// The order-`2^k` multiplicative subgroup `{ omega^i | i in 0..2^k }` of `Fld`,
+/// generic over any [`Fft2AdicField`] backend.
+pub fn fft2adic_subgroup<Fld: Fft2AdicField>(k: usize) -> Vec<Fld> {
+    let omega = Fld::root_of_unity(k);
+    let size = 1usize << k;
+    let mut out = Vec::with_capacity(size);
+    let mut acc = Fld::one();
+    for _ in 0..size {
+        out.push(acc);
+        acc = acc * omega;
+    }
+    out
+}
+
+/// A minimal Goldilocks field (`p = 2^64 - 2^32 + 1`), the 64-bit backend
+/// [`Fft2AdicField`] benchmarks against Pallas's 255-bit `Fr` (see the
+/// `fft2adic_domain` group in `channel`'s `end_to_end` bench). Scoped to exactly
+/// what `Fft2AdicField` and the FRI fold need -- it is not `ark_ff::Field` and has no
+/// `CanonicalSerialize` impl. Reduction goes through `u128` throughout rather than the
+/// field-specific fast-reduction trick (shift-and-subtract on `2^32 - 1`) every
+/// production Goldilocks implementation uses; that optimization is a follow-up once
+/// this backend needs to carry real proof workloads instead of just domain/fold
+/// benchmarks.
+pub mod goldilocks {
+    use super::Fft2AdicField;
+
+    /// p = 2^64 - 2^32 + 1.
+    pub const MODULUS: u64 = 0xFFFF_FFFF_0000_0001;
+
+    /// The standard order-`2^32` element used by Plonky2/RISC0's Goldilocks
+    /// implementations (Goldilocks has two-adicity 32, since `p - 1 = 2^32 * (2^32 -
+    /// 1)`).
+    const TWO_ADIC_GENERATOR: u64 = 1_753_635_133_440_165_772;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct Goldilocks(pub u64);
+
+    impl Goldilocks {
+        pub fn new(x: u64) -> Self {
+            Self(x % MODULUS)
+        }
+    }
+
+    impl std::ops::Add for Goldilocks {
+        type Output = Self;
+        fn add(self, rhs: Self) -> Self {
+            Self(((self.0 as u128 + rhs.0 as u128) % MODULUS as u128) as u64)
+        }
+    }
+
+    impl std::ops::Sub for Goldilocks {
+        type Output = Self;
+        fn sub(self, rhs: Self) -> Self {
+            self + (-rhs)
+        }
+    }
+
+    impl std::ops::Mul for Goldilocks {
+        type Output = Self;
+        fn mul(self, rhs: Self) -> Self {
+            Self(((self.0 as u128 * rhs.0 as u128) % MODULUS as u128) as u64)
+        }
+    }
+
+    impl std::ops::Neg for Goldilocks {
+        type Output = Self;
+        fn neg(self) -> Self {
+            if self.0 == 0 {
+                self
+            } else {
+                Self(MODULUS - self.0)
+            }
+        }
+    }
+
+    impl Fft2AdicField for Goldilocks {
+        const TWO_ADICITY: usize = 32;
+
+        fn zero() -> Self {
+            Self(0)
+        }
+
+        fn one() -> Self {
+            Self(1)
+        }
+
+        fn two_adic_generator() -> Self {
+            Self(TWO_ADIC_GENERATOR)
+        }
+
+        fn pow_u64(self, exp: u64) -> Self {
+            let mut base = self;
+            let mut acc = Self::one();
+            let mut e = exp;
+            while e > 0 {
+                if e & 1 == 1 {
+                    acc = acc * base;
+                }
+                base = base * base;
+                e >>= 1;
+            }
+            acc
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn two_adic_generator_has_exact_order_2_pow_32() {
+            let g = Goldilocks::two_adic_generator();
+            assert_eq!(g.pow_u64(1u64 << 32), Goldilocks::one());
+            assert_ne!(g.pow_u64(1u64 << 31), Goldilocks::one());
+        }
+
+        #[test]
+        fn root_of_unity_has_exact_order_2_pow_k() {
+            for k in [0usize, 1, 4, 8, 16] {
+                let omega = Goldilocks::root_of_unity(k);
+                assert_eq!(omega.pow_u64(1u64 << k), Goldilocks::one());
+                if k > 0 {
+                    assert_ne!(omega.pow_u64(1u64 << (k - 1)), Goldilocks::one());
+                }
+            }
+        }
+
+        #[test]
+        #[should_panic(expected = "exceeds this field's two-adicity")]
+        fn root_of_unity_rejects_k_above_two_adicity() {
+            let _ = Goldilocks::root_of_unity(33);
+        }
+
+        #[test]
+        fn neg_and_sub_are_consistent() {
+            let a = Goldilocks::new(5);
+            let b = Goldilocks::new(9);
+            assert_eq!(a - b, a + (-b));
+            assert_eq!(a + (-a), Goldilocks::zero());
+        }
+    }
+}
+
 /// Construct the canonical size-2048 multiplicative subgroup H in F.
 ///
 /// Returns (omega, N) where:
@@ -214,6 +694,660 @@ pub fn fr_from_bytes_compressed(bytes: &[u8]) -> Result<F, ark_serialize::Serial
     F::deserialize_with_mode(bytes, Compress::Yes, Validate::Yes)
 }
 
+/// FRI low-degree test built directly on `Domain`.
+///
+/// Exposes the parameters Winterfell surfaces for this protocol (`blowup_factor`,
+/// `num_queries`, `grinding_bits`) rather than the layer `schedule`/`seed_z` pair
+/// `deep_ali::fri` uses -- a deliberately independent, `Domain`-native implementation,
+/// not a wrapper around that one.
+///
+/// Commit phase: starting from evaluations on a domain of size `N`, fold repeatedly
+/// with a Fiat-Shamir challenge `alpha`:
+/// `f'(x^2) = (f(x) + f(-x)) / 2 + alpha * (f(x) - f(-x)) / (2x)`,
+/// halving the domain each round (new generator `omega^2`), Merkle-committing each
+/// layer's codeword, until the codeword is constant or its length drops to
+/// `blowup_factor`. Query phase: derive `num_queries` positions from the transcript
+/// and open the `(x, -x)` pair at each layer, so the verifier can recompute every
+/// fold and check it against the next layer's opening (or the final constant).
+/// Grinding: the prover must find a nonce such that hashing it into the transcript
+/// yields at least `grinding_bits` leading zero bits before positions are sampled.
+pub mod fri {
+    use super::{Domain, F};
+    use ark_ff::{BigInteger, Field, PrimeField};
+    // Leading `::` picks the `merkle` crate over this crate's own `merkle` module
+    // (the vector-commitment one added alongside this module).
+    use ::merkle::{MerkleChannelCfg, MerkleProof, MerkleProver, MerkleTree};
+    use transcript::PoseidonTranscript;
+
+    pub struct FriParams {
+        pub blowup_factor: usize,
+        pub num_queries: usize,
+        pub grinding_bits: u32,
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct FriLayerOpening {
+        pub value_pos: F,
+        pub value_neg: F,
+        pub proof: MerkleProof,
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct FriQueryOpening {
+        pub position: usize,
+        pub layers: Vec<FriLayerOpening>,
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct FriProof {
+        pub layer_roots: Vec<F>,
+        pub query_openings: Vec<FriQueryOpening>,
+        pub final_constant: F,
+        pub nonce: u64,
+    }
+
+    fn is_constant(codeword: &[F]) -> bool {
+        codeword.iter().all(|v| *v == codeword[0])
+    }
+
+    // The fold relation itself: f'(x^2) = (f(x)+f(-x))/2 + alpha*(f(x)-f(-x))/(2x).
+    // Shared by the prover's layer-at-a-time fold and the verifier's per-query
+    // recomputation, so the two can never drift apart.
+    fn fold_pair(f_x: F, f_neg_x: F, x_inv: F, alpha: F, two_inv: F) -> F {
+        let even = (f_x + f_neg_x) * two_inv;
+        let odd = (f_x - f_neg_x) * two_inv * x_inv;
+        even + alpha * odd
+    }
+
+    fn fold_codeword(codeword: &[F], domain: &Domain, alpha: F) -> Vec<F> {
+        let half = domain.size / 2;
+        let two_inv = F::from(2u64).inverse().expect("2 is never zero in Fr");
+        (0..half)
+            .map(|pos| {
+                let x_inv = domain.element(pos).inverse().expect("domain elements are never zero");
+                fold_pair(codeword[pos], codeword[pos + half], x_inv, alpha, two_inv)
+            })
+            .collect()
+    }
+
+    // The halved domain a fold round moves into: size n/2, generator omega^2. Built
+    // directly rather than via `Domain::new` so it shares the exact same generator
+    // the fold relation assumes, not merely *a* primitive root of the right order.
+    fn half_domain(domain: &Domain) -> Domain {
+        Domain {
+            size: domain.size / 2,
+            log_n: domain.log_n - 1,
+            omega: domain.omega * domain.omega,
+            omega_inv: domain.omega_inv * domain.omega_inv,
+            n_inv: domain.n_inv * F::from(2u64),
+            elements: Vec::new(),
+        }
+    }
+
+    fn leading_zero_bits(x: &F) -> u32 {
+        x.into_bigint().to_bits_be().iter().take_while(|b| !**b).count() as u32
+    }
+
+    fn grind_nonce(transcript: &PoseidonTranscript, grinding_bits: u32) -> u64 {
+        if grinding_bits == 0 {
+            return 0;
+        }
+        let mut nonce = 0u64;
+        loop {
+            let digest = transcript.peek_challenge(&nonce.to_le_bytes());
+            if leading_zero_bits(&digest) >= grinding_bits {
+                return nonce;
+            }
+            nonce += 1;
+        }
+    }
+
+    fn merkle_cfg_for_layer(layer: usize) -> MerkleChannelCfg {
+        MerkleChannelCfg::new(2).with_tree_label(layer as u64)
+    }
+
+    /// Commit to `evals` (evaluations of some polynomial on `domain`) and prove, via
+    /// repeated folding, that they're close to low-degree. `label` seeds the
+    /// Fiat-Shamir transcript the same way every other `transcript::PoseidonTranscript`
+    /// user in this repo seeds theirs.
+    pub fn prove(label: &[u8], evals: Vec<F>, domain: Domain, params: &FriParams) -> FriProof {
+        assert_eq!(evals.len(), domain.size, "evals must match the domain's size");
+        assert!(domain.size.is_power_of_two(), "domain size must be a power of two");
+        assert!(
+            params.blowup_factor > 0 && params.blowup_factor.is_power_of_two(),
+            "blowup_factor must be a power of two"
+        );
+
+        let n0 = domain.size;
+        let mut transcript = PoseidonTranscript::new(label, transcript::default_params());
+        transcript.absorb_field(F::from(n0 as u64));
+        transcript.absorb_field(F::from(params.blowup_factor as u64));
+
+        let cutoff = params.blowup_factor;
+        let mut cur_domain = domain;
+        let mut cur_codeword = evals;
+
+        let mut layer_roots: Vec<F> = Vec::new();
+        let mut layer_domains: Vec<Domain> = Vec::new();
+        let mut layer_codewords: Vec<Vec<F>> = Vec::new();
+        let mut layer_trees: Vec<MerkleTree> = Vec::new();
+
+        while cur_codeword.len() > cutoff && !is_constant(&cur_codeword) {
+            let prover = MerkleProver::new(merkle_cfg_for_layer(layer_roots.len()));
+            let (root, tree) = prover.commit_single(&cur_codeword);
+            transcript.absorb_field(root);
+            let alpha = transcript.challenge(b"fri-fold-alpha");
+
+            let folded = fold_codeword(&cur_codeword, &cur_domain, alpha);
+
+            layer_roots.push(root);
+            layer_domains.push(cur_domain.clone());
+            layer_codewords.push(cur_codeword);
+            layer_trees.push(tree);
+
+            cur_domain = half_domain(&cur_domain);
+            cur_codeword = folded;
+        }
+
+        let final_constant = cur_codeword[0];
+        transcript.absorb_field(final_constant);
+
+        let nonce = grind_nonce(&transcript, params.grinding_bits);
+        transcript.absorb_field(F::from(nonce));
+
+        let positions =
+            transcript.sample_query_indices_distinct(b"fri-query-positions", n0, params.num_queries);
+
+        let num_layers = layer_roots.len();
+        let query_openings = positions
+            .iter()
+            .map(|&q0| {
+                let mut idx = q0;
+                let layers = (0..num_layers)
+                    .map(|i| {
+                        let half = layer_domains[i].size / 2;
+                        let pos = idx % half;
+                        let neg = pos + half;
+                        let prover = MerkleProver::new(merkle_cfg_for_layer(i));
+                        let proof = prover.open_single(&layer_trees[i], &[pos, neg]);
+                        let opening = FriLayerOpening {
+                            value_pos: layer_codewords[i][pos],
+                            value_neg: layer_codewords[i][neg],
+                            proof,
+                        };
+                        idx = pos;
+                        opening
+                    })
+                    .collect();
+                FriQueryOpening { position: q0, layers }
+            })
+            .collect();
+
+        FriProof { layer_roots, query_openings, final_constant, nonce }
+    }
+
+    /// Verify a `FriProof` produced by `prove` for evaluations claimed to live on
+    /// `domain` (the same domain the prover started from).
+    pub fn verify(label: &[u8], domain: &Domain, params: &FriParams, proof: &FriProof) -> bool {
+        let n0 = domain.size;
+        let mut transcript = PoseidonTranscript::new(label, transcript::default_params());
+        transcript.absorb_field(F::from(n0 as u64));
+        transcript.absorb_field(F::from(params.blowup_factor as u64));
+
+        let mut alphas = Vec::with_capacity(proof.layer_roots.len());
+        for &root in &proof.layer_roots {
+            transcript.absorb_field(root);
+            alphas.push(transcript.challenge(b"fri-fold-alpha"));
+        }
+        transcript.absorb_field(proof.final_constant);
+
+        if params.grinding_bits > 0 {
+            let digest = transcript.peek_challenge(&proof.nonce.to_le_bytes());
+            if leading_zero_bits(&digest) < params.grinding_bits {
+                return false;
+            }
+        }
+        transcript.absorb_field(F::from(proof.nonce));
+
+        let positions =
+            transcript.sample_query_indices_distinct(b"fri-query-positions", n0, params.num_queries);
+        if positions.len() != proof.query_openings.len() {
+            return false;
+        }
+
+        let num_layers = proof.layer_roots.len();
+        let mut sizes_and_omegas = Vec::with_capacity(num_layers);
+        let mut size = n0;
+        let mut omega = domain.omega;
+        for _ in 0..num_layers {
+            sizes_and_omegas.push((size, omega));
+            size /= 2;
+            omega *= omega;
+        }
+
+        let two_inv = F::from(2u64).inverse().expect("2 is never zero in Fr");
+
+        for (&q0, opening) in positions.iter().zip(proof.query_openings.iter()) {
+            if opening.position != q0 || opening.layers.len() != num_layers {
+                return false;
+            }
+            let mut idx = q0;
+            for i in 0..num_layers {
+                let (size_i, omega_i) = sizes_and_omegas[i];
+                let half = size_i / 2;
+                let pos = idx % half;
+                let neg = pos + half;
+                let layer = &opening.layers[i];
+
+                let prover = MerkleProver::new(merkle_cfg_for_layer(i));
+                let leaves = [layer.value_pos, layer.value_neg];
+                if !prover.verify_single(&proof.layer_roots[i], &[pos, neg], &leaves, &layer.proof) {
+                    return false;
+                }
+
+                let x = omega_i.pow([pos as u64]);
+                let x_inv = match x.inverse() {
+                    Some(v) => v,
+                    None => return false,
+                };
+                let folded = fold_pair(layer.value_pos, layer.value_neg, x_inv, alphas[i], two_inv);
+                idx = pos;
+
+                if i + 1 < num_layers {
+                    let next = &opening.layers[i + 1];
+                    let next_half = half / 2;
+                    let expected = if idx < next_half { next.value_pos } else { next.value_neg };
+                    if folded != expected {
+                        return false;
+                    }
+                } else if folded != proof.final_constant {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn poly_evals(coeffs: &[F], domain: &Domain) -> Vec<F> {
+            let mut buf = coeffs.to_vec();
+            buf.resize(domain.size, F::zero());
+            domain.ntt(&mut buf).expect("ntt on exactly-sized input must succeed");
+            buf
+        }
+
+        #[test]
+        fn prove_and_verify_roundtrip_for_a_low_degree_poly() {
+            let domain = Domain::new(6).expect("root of unity must exist"); // size = 64
+            let coeffs: Vec<F> = (0..8u64).map(F::from).collect(); // degree < 8
+            let evals = poly_evals(&coeffs, &domain);
+
+            let params = FriParams { blowup_factor: 4, num_queries: 12, grinding_bits: 4 };
+            let proof = prove(b"fri-test", evals, domain.clone(), &params);
+
+            assert!(verify(b"fri-test", &domain, &params, &proof));
+        }
+
+        #[test]
+        fn verify_rejects_a_tampered_proof() {
+            let domain = Domain::new(6).expect("root of unity must exist"); // size = 64
+            let coeffs: Vec<F> = (0..8u64).map(F::from).collect();
+            let evals = poly_evals(&coeffs, &domain);
+
+            let params = FriParams { blowup_factor: 4, num_queries: 12, grinding_bits: 0 };
+            let mut proof = prove(b"fri-test", evals, domain.clone(), &params);
+            proof.final_constant += F::from(1u64);
+
+            assert!(!verify(b"fri-test", &domain, &params, &proof));
+        }
+
+        #[test]
+        fn verify_rejects_high_degree_evaluations() {
+            let domain = Domain::new(6).expect("root of unity must exist"); // size = 64
+            // A random-looking, effectively full-degree codeword should not fold to a
+            // constant by the time its length reaches blowup_factor.
+            let evals: Vec<F> = (0..domain.size as u64).map(|i| F::from(i * i + 1)).collect();
+
+            let params = FriParams { blowup_factor: 4, num_queries: 16, grinding_bits: 0 };
+            let proof = prove(b"fri-test-high-degree", evals, domain.clone(), &params);
+
+            assert!(!verify(b"fri-test-high-degree", &domain, &params, &proof));
+        }
+
+        #[test]
+        fn grinding_nonce_meets_the_requested_difficulty() {
+            let domain = Domain::new(5).expect("root of unity must exist"); // size = 32
+            let coeffs: Vec<F> = (0..4u64).map(F::from).collect();
+            let evals = poly_evals(&coeffs, &domain);
+
+            let params = FriParams { blowup_factor: 4, num_queries: 8, grinding_bits: 8 };
+            let proof = prove(b"fri-grind", evals, domain.clone(), &params);
+
+            assert!(verify(b"fri-grind", &domain, &params, &proof));
+        }
+    }
+}
+
+/// A binary Poseidon Merkle tree over `Vec<F>` leaves, modeled on ginger-lib's
+/// field-based Merkle tree: the commitment primitive `fri` (and any polynomial
+/// commitment built on `Domain`) needs, reusing the t=17 Poseidon permutation
+/// already benchmarked in `crates/bench`.
+///
+/// Unlike `crates/merkle`'s `MerkleTree` (leaves are single `F`s or `(F, F)` pairs,
+/// opened as union-of-paths multiproofs), this commits to a *vector* per leaf and
+/// proves one leaf at a time -- a smaller, independent primitive, not a wrapper
+/// around that crate.
+pub mod merkle {
+    use super::F;
+    use ark_ff::{PrimeField, Zero};
+    use poseidon::{hash_with_ds, params::generate_params_t17_x5, PoseidonParams};
+
+    fn params() -> PoseidonParams {
+        generate_params_t17_x5(b"FIELD-MERKLE-T17-X5")
+    }
+
+    // Map a fixed label to a field element, the same way `transcript`'s
+    // `domain_tag_to_field` does, to tell leaf/node/padding absorptions apart.
+    fn tag(label: &[u8]) -> F {
+        let mut le = [0u8; 32];
+        let n = label.len().min(32);
+        le[..n].copy_from_slice(&label[..n]);
+        F::from_le_bytes_mod_order(&le)
+    }
+
+    fn leaf_tag() -> F {
+        tag(b"FIELD-MERKLE-LEAF")
+    }
+
+    fn node_tag() -> F {
+        tag(b"FIELD-MERKLE-NODE")
+    }
+
+    fn padding_tag() -> F {
+        tag(b"FIELD-MERKLE-PAD")
+    }
+
+    fn hash_leaf(params: &PoseidonParams, leaf: &[F]) -> F {
+        hash_with_ds(leaf, leaf_tag(), params)
+    }
+
+    // The 2-to-1 compression: absorb both children into the sponge and squeeze one F.
+    fn hash_node(params: &PoseidonParams, left: F, right: F) -> F {
+        hash_with_ds(&[left, right], node_tag(), params)
+    }
+
+    // Fixed domain-separated zero digest used to pad leaf counts up to the next
+    // power of two -- distinguishable from any real leaf hash by `padding_tag`.
+    fn padding_digest(params: &PoseidonParams) -> F {
+        hash_with_ds(&[F::zero()], padding_tag(), params)
+    }
+
+    /// Sibling digests bottom-to-top for one leaf, plus the leaf's index (which
+    /// determines, at each level, whether the sibling is the left or right child).
+    #[derive(Clone, Debug)]
+    pub struct MerklePath {
+        pub index: usize,
+        pub siblings: Vec<F>,
+    }
+
+    impl MerklePath {
+        /// Stateless verification: recompute the leaf digest and climb the path,
+        /// comparing the final digest against `root`.
+        pub fn verify(&self, root: F, leaf: &[F]) -> bool {
+            let params = params();
+            let mut digest = hash_leaf(&params, leaf);
+            let mut idx = self.index;
+            for &sibling in &self.siblings {
+                digest = if idx % 2 == 0 {
+                    hash_node(&params, digest, sibling)
+                } else {
+                    hash_node(&params, sibling, digest)
+                };
+                idx /= 2;
+            }
+            digest == root
+        }
+    }
+
+    /// A binary Poseidon Merkle tree committing to `leaves`, padded up to the next
+    /// power of two with `padding_digest`.
+    pub struct MerkleTree {
+        num_leaves: usize,
+        // levels[0] holds the (padded) leaf digests; levels.last() is the root.
+        levels: Vec<Vec<F>>,
+    }
+
+    impl MerkleTree {
+        pub fn new(leaves: &[Vec<F>]) -> Self {
+            assert!(!leaves.is_empty(), "MerkleTree needs at least one leaf");
+            let params = params();
+            let padded_size = leaves.len().next_power_of_two();
+
+            let mut level: Vec<F> = leaves.iter().map(|l| hash_leaf(&params, l)).collect();
+            level.resize(padded_size, padding_digest(&params));
+
+            let mut levels = vec![level];
+            while levels.last().unwrap().len() > 1 {
+                let next = levels
+                    .last()
+                    .unwrap()
+                    .chunks(2)
+                    .map(|pair| hash_node(&params, pair[0], pair[1]))
+                    .collect();
+                levels.push(next);
+            }
+
+            Self { num_leaves: leaves.len(), levels }
+        }
+
+        pub fn root(&self) -> F {
+            self.levels.last().expect("at least one level always exists")[0]
+        }
+
+        /// Build the sibling path (bottom-to-top) proving `leaf_index`.
+        pub fn prove(&self, leaf_index: usize) -> MerklePath {
+            assert!(leaf_index < self.num_leaves, "leaf_index out of range");
+            let mut idx = leaf_index;
+            let mut siblings = Vec::with_capacity(self.levels.len() - 1);
+            for level in &self.levels[..self.levels.len() - 1] {
+                siblings.push(level[idx ^ 1]);
+                idx /= 2;
+            }
+            MerklePath { index: leaf_index, siblings }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn prove_and_verify_roundtrip() {
+            let leaves: Vec<Vec<F>> = (0..5u64).map(|i| vec![F::from(i), F::from(i * i)]).collect();
+            let tree = MerkleTree::new(&leaves);
+            let root = tree.root();
+
+            for (i, leaf) in leaves.iter().enumerate() {
+                assert!(tree.prove(i).verify(root, leaf));
+            }
+        }
+
+        #[test]
+        fn verify_rejects_the_wrong_leaf() {
+            let leaves: Vec<Vec<F>> = (0..4u64).map(|i| vec![F::from(i)]).collect();
+            let tree = MerkleTree::new(&leaves);
+            let root = tree.root();
+            let path = tree.prove(2);
+            assert!(!path.verify(root, &leaves[1]));
+        }
+
+        #[test]
+        fn pads_non_power_of_two_leaf_counts() {
+            let leaves: Vec<Vec<F>> = (0..3u64).map(|i| vec![F::from(i)]).collect(); // pads to 4
+            let tree = MerkleTree::new(&leaves);
+            assert_eq!(tree.levels.len(), 3); // 4 leaves -> 2 -> 1 (root)
+
+            let root = tree.root();
+            assert!(tree.prove(0).verify(root, &leaves[0]));
+        }
+    }
+}
+
+/// The grand-product permutation argument PLONK/powdr-style circuits use to check
+/// that a column `a` is consistent with itself under a permutation `sigma` over the
+/// multiplicative subgroup `H` -- the running-product accumulator powdr's
+/// `permutation.asm` formalizes, built here on top of `Domain`.
+pub mod permutation {
+    use super::F;
+    use ark_ff::{One, Zero};
+
+    /// Batch-invert `values` with a single field inversion (the Montgomery trick),
+    /// instead of one inversion per element.
+    fn batch_inverse(values: &[F]) -> Vec<F> {
+        assert!(!values.is_empty(), "non-empty input");
+        let mut prefix = Vec::with_capacity(values.len());
+        let mut acc = F::one();
+        for &v in values {
+            prefix.push(acc);
+            acc *= v;
+        }
+        let mut acc_inv = acc.inverse().expect("product of denominators must be nonzero");
+
+        let mut out = vec![F::zero(); values.len()];
+        for i in (0..values.len()).rev() {
+            out[i] = prefix[i] * acc_inv;
+            acc_inv *= values[i];
+        }
+        out
+    }
+
+    /// The identity mapping values `id_i = omega^i` for a domain of `n = a.len()`
+    /// column entries -- the natural `id` argument to `build_grand_product` when the
+    /// caller doesn't already have one from elsewhere.
+    pub fn identity_values(domain: &super::Domain) -> Vec<F> {
+        domain.iter().collect()
+    }
+
+    /// The running-product accumulator `Z`, in evaluation form over `H`
+    /// (`z[i] = Z(omega^i)`), plus what's needed to check it wraps back around to 1.
+    pub struct GrandProduct {
+        pub z: Vec<F>,
+    }
+
+    impl GrandProduct {
+        /// Asserts `Z(omega^n) = Z(omega^0) = 1`, i.e. that the running product
+        /// returns to 1 after one full pass around `H` -- which certifies `{a_i}` is
+        /// a permutation of itself under `sigma`. Takes the same columns/challenges
+        /// `build_grand_product` did, since the final step isn't stored in `z`.
+        pub fn check_wraparound(&self, a: &[F], id: &[F], sigma: &[F], beta: F, gamma: F) -> bool {
+            let n = self.z.len();
+            if n == 0 || self.z[0] != F::one() {
+                return false;
+            }
+            let last = n - 1;
+            let numer = a[last] + beta * id[last] + gamma;
+            let denom = a[last] + beta * sigma[last] + gamma;
+            let denom_inv = match denom.inverse() {
+                Some(v) => v,
+                None => return false,
+            };
+            let wrapped = self.z[last] * numer * denom_inv;
+            wrapped == F::one()
+        }
+    }
+
+    /// Build `Z` on `H`: `Z(omega^0) = 1`,
+    /// `Z(omega^{i+1}) = Z(omega^i) * (a_i + beta*id_i + gamma) / (a_i + beta*sigma_i + gamma)`,
+    /// using a single batch inversion for the denominators. Returned in evaluation
+    /// form, ready to be committed directly or interpolated via `Domain::ntt`.
+    pub fn build_grand_product(a: &[F], id: &[F], sigma: &[F], beta: F, gamma: F) -> GrandProduct {
+        let n = a.len();
+        assert!(n > 0, "non-empty domain");
+        assert_eq!(id.len(), n, "id must match a's length");
+        assert_eq!(sigma.len(), n, "sigma must match a's length");
+
+        let denom: Vec<F> = (0..n).map(|i| a[i] + beta * sigma[i] + gamma).collect();
+        let denom_inv = batch_inverse(&denom);
+
+        let mut z = Vec::with_capacity(n);
+        let mut acc = F::one();
+        z.push(acc);
+        for i in 0..n - 1 {
+            let numer = a[i] + beta * id[i] + gamma;
+            acc *= numer * denom_inv[i];
+            z.push(acc);
+        }
+
+        GrandProduct { z }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::Domain;
+
+        #[test]
+        fn accepts_a_genuine_transposition() {
+            let domain = Domain::new(3).expect("root of unity must exist"); // size = 8
+            let id = identity_values(&domain);
+
+            // sigma swaps the id values at positions 2 and 5 (a copy constraint
+            // tying a_2 to a_5) and leaves everything else fixed.
+            let mut sigma = id.clone();
+            sigma.swap(2, 5);
+
+            let mut a: Vec<F> = (0..8u64).map(F::from).collect();
+            a[5] = a[2]; // satisfy the copy constraint the transposition demands
+
+            let beta = F::from(7u64);
+            let gamma = F::from(13u64);
+            let z = build_grand_product(&a, &id, &sigma, beta, gamma);
+
+            assert_eq!(z.z.len(), 8);
+            assert_eq!(z.z[0], F::one());
+            assert!(z.check_wraparound(&a, &id, &sigma, beta, gamma));
+        }
+
+        #[test]
+        fn grand_product_wraps_around_for_a_true_self_permutation() {
+            let domain = Domain::new(3).expect("root of unity must exist"); // size = 8
+            let id = identity_values(&domain);
+
+            // sigma is the identity permutation itself: trivially, `a` is a
+            // permutation of itself under it, so the grand product must wrap to 1.
+            let sigma = id.clone();
+            let a: Vec<F> = (0..8u64).map(|i| F::from(i * i + 1)).collect();
+
+            let beta = F::from(11u64);
+            let gamma = F::from(5u64);
+            let z = build_grand_product(&a, &id, &sigma, beta, gamma);
+
+            assert!(z.check_wraparound(&a, &id, &sigma, beta, gamma));
+        }
+
+        #[test]
+        fn grand_product_rejects_a_mismatched_permutation() {
+            let domain = Domain::new(3).expect("root of unity must exist"); // size = 8
+            let id = identity_values(&domain);
+
+            let sigma = id.clone();
+            let a: Vec<F> = (0..8u64).map(F::from).collect();
+            let mut a_wrong = a.clone();
+            a_wrong[0] += F::one(); // break self-consistency under the identity permutation
+
+            let beta = F::from(11u64);
+            let gamma = F::from(5u64);
+            let z = build_grand_product(&a_wrong, &id, &sigma, beta, gamma);
+
+            assert!(!z.check_wraparound(&a_wrong, &id, &sigma, beta, gamma));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -247,6 +1381,122 @@ mod tests {
         assert_eq!(iter_elems[2], dom.omega * dom.omega);
     }
 
+    #[test]
+    fn test_ntt_intt_roundtrip() {
+        let dom = Domain::new(4).expect("root of unity must exist"); // size = 16
+        let coeffs: Vec<F> = (0..dom.size as u64).map(F::from).collect();
+
+        let mut evals = coeffs.clone();
+        dom.ntt(&mut evals).expect("ntt on exactly-sized input must succeed");
+
+        let mut back = evals.clone();
+        dom.intt(&mut back).expect("intt on exactly-sized input must succeed");
+        assert_eq!(back, coeffs);
+    }
+
+    #[test]
+    fn test_ntt_matches_direct_evaluation() {
+        let dom = Domain::new(3).expect("root of unity must exist"); // size = 8
+        let coeffs: Vec<F> = (0..dom.size as u64).map(|i| F::from(i + 1)).collect();
+
+        let mut evals = coeffs.clone();
+        dom.ntt(&mut evals).unwrap();
+
+        for (k, &ev) in evals.iter().enumerate() {
+            let x = dom.element(k);
+            let mut acc = F::zero();
+            for &c in coeffs.iter().rev() {
+                acc = acc * x + c;
+            }
+            assert_eq!(ev, acc);
+        }
+    }
+
+    #[test]
+    fn test_coset_lde_intt_roundtrip() {
+        let dom = Domain::new(2).expect("root of unity must exist"); // size = 4
+        let coeffs: Vec<F> = (0..dom.size as u64).map(|i| F::from(i + 1)).collect();
+
+        let coset = CosetDomain::new(&dom, 4);
+        let evals = coset.lde(&coeffs);
+        assert_eq!(evals.len(), dom.size * 4);
+
+        let back = coset.intt(&evals);
+        assert_eq!(back, coeffs);
+
+        // `Domain::coset_lde`/`coset_intt` are shorthand for the same thing.
+        let evals2 = dom.coset_lde(&coeffs, 4);
+        assert_eq!(evals2, evals);
+        assert_eq!(dom.coset_intt(&evals2, 4), coeffs);
+    }
+
+    #[test]
+    fn test_coset_lde_matches_direct_evaluation_off_the_subgroup() {
+        let dom = Domain::new(2).expect("root of unity must exist"); // size = 4
+        let coeffs: Vec<F> = vec![F::from(3u64), F::from(5u64), F::from(7u64), F::from(11u64)];
+
+        let coset = CosetDomain::new(&dom, 2);
+        let evals = coset.lde(&coeffs);
+
+        for (k, &ev) in evals.iter().enumerate() {
+            let x = coset.offset * coset.domain.element(k);
+            let mut acc = F::zero();
+            for &c in coeffs.iter().rev() {
+                acc = acc * x + c;
+            }
+            assert_eq!(ev, acc);
+            // The coset point is never a root of unity of the enlarged domain.
+            assert!(!x.pow([coset.domain.size as u64]).is_one());
+        }
+    }
+
+    #[test]
+    fn test_ntt_rejects_wrong_length() {
+        let dom = Domain::new(3).expect("root of unity must exist"); // size = 8
+        let mut too_short = vec![F::zero(); 4];
+        assert_eq!(
+            dom.ntt(&mut too_short),
+            Err(NttError::LengthMismatch { expected: 8, got: 4 })
+        );
+    }
+
+    #[test]
+    fn test_interpolate_is_the_inverse_of_ntt() {
+        let dom = Domain::new(3).expect("root of unity must exist"); // size = 8
+        let coeffs: Vec<F> = (0..dom.size as u64).map(F::from).collect();
+
+        let mut evals = coeffs.clone();
+        dom.ntt(&mut evals).unwrap();
+        assert_eq!(dom.interpolate(&evals), coeffs);
+    }
+
+    #[test]
+    fn test_eval_barycentric_matches_direct_evaluation_off_the_subgroup() {
+        let dom = Domain::new(3).expect("root of unity must exist"); // size = 8
+        let coeffs: Vec<F> = (0..dom.size as u64).map(|i| F::from(i + 1)).collect();
+
+        let mut evals = coeffs.clone();
+        dom.ntt(&mut evals).unwrap();
+
+        let z = F::from(999u64);
+        let mut direct = F::zero();
+        for &c in coeffs.iter().rev() {
+            direct = direct * z + c;
+        }
+        assert_eq!(dom.eval_barycentric(&evals, z), direct);
+    }
+
+    #[test]
+    fn test_eval_barycentric_on_a_domain_point_returns_the_eval_directly() {
+        let dom = Domain::new(3).expect("root of unity must exist"); // size = 8
+        let evals: Vec<F> = (0..dom.size as u64).map(|i| F::from(i * 3 + 1)).collect();
+
+        for (i, &v) in evals.iter().enumerate() {
+            let z = dom.element(i);
+            assert_eq!(dom.eval_barycentric(&evals, z), v);
+        }
+    }
+
     #[test]
     fn test_serialize_roundtrip() {
         let x = F::from(42u64);
@@ -255,6 +1505,32 @@ mod tests {
         assert_eq!(x, y);
     }
 
+    #[test]
+    fn fft2adic_root_of_unity_matches_get_root_of_unity_for_pallas() {
+        for k in [0usize, 1, 3, 8, 11] {
+            let omega = <F as Fft2AdicField>::root_of_unity(k);
+            let expected = F::get_root_of_unity(1u64 << k).expect("root of unity exists");
+            assert_eq!(omega, expected);
+        }
+    }
+
+    #[test]
+    fn fft2adic_subgroup_matches_compute_powers_for_pallas() {
+        let k = 6;
+        let omega = <F as Fft2AdicField>::root_of_unity(k);
+        let expected = compute_powers(omega, 1usize << k);
+        let got: Vec<F> = fft2adic_subgroup(k);
+        assert_eq!(got, expected);
+    }
+
+    #[test]
+    fn fft2adic_fold_pair_is_linear_in_beta() {
+        let f_even = F::from(3u64);
+        let f_odd = F::from(5u64);
+        let beta = F::from(7u64);
+        assert_eq!(fft2adic_fold_pair(f_even, f_odd, beta), f_even + beta * f_odd);
+    }
+
     #[test]
     fn test_domain_2048_ok() {
         let (omega, n) = make_domain_2048();
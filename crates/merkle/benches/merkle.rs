@@ -5,28 +5,10 @@ use rand::{rngs::StdRng, SeedableRng};
 use merkle::{MerkleChannelCfg, MerkleTree, MerkleProof, verify_many_ds};
 use poseidon::{params::generate_params_t17_x5, dynamic_from_static_t17};
 
-// Serialize a proof deterministically to count bytes, without requiring serde in your lib.
+// Real compressed wire size via `MerkleProof`'s `CanonicalSerialize` impl, replacing the
+// previous guessed fixed-32-bytes-per-field accounting.
 fn proof_size_bytes(proof: &MerkleProof) -> usize {
-    // Layout:
-    // - arity: u8
-    // - group_sizes: for each level: len(u64) + bytes of sizes(u8 each)
-    // - siblings: for each level: len(u64) + len * Fr (32 bytes if compressed via to_bytes_le padded to 32)
-    let mut total = 0usize;
-    total += 1; // arity
-    total += 8; // number of levels for group_sizes (implicit via vec length)
-    for lvl in &proof.group_sizes {
-        total += 8; // len
-        total += lvl.len(); // each size as 1 byte
-    }
-    total += 8; // number of levels for siblings
-    for lvl in &proof.siblings {
-        total += 8; // len
-        // Field size accounting: using canonical little-endian; pad to 32 bytes.
-        for _s in lvl {
-            total += 32;
-        }
-    }
-    total
+    proof.serialized_size()
 }
 
 fn bench_merkle_build_open_verify(c: &mut Criterion) {
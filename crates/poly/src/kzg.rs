@@ -0,0 +1,254 @@
+//! KZG10 polynomial commitment scheme over the pairing-friendly BLS12-381 curve, built
+//! on this crate's `Poly` wrapper (at its default [`crate::Bls12_381Fr`]). `FRI`
+//! (`deep_ali::fri`) is the field-agnostic low-degree test this repo already has; KZG10
+//! is the alternative, constant-size pairing check available once a pairing-friendly
+//! curve is on the table.
+
+use ark_bls12_381::{Bls12_381, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::pairing::Pairing;
+use ark_ec::{CurveGroup, Group};
+use ark_ff::{UniformRand, Zero};
+use rand::Rng;
+
+use deep_ali::DomainH;
+
+use crate::{Bls12_381Fr as F, Poly};
+
+/// The only ways committing/opening against a `UniversalParams` can fail: the
+/// polynomial (or, for `open_on_domain`, the evaluation domain) doesn't fit under the
+/// SRS's max degree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    PolynomialDegreeTooLarge { degree: usize, max_degree: usize },
+    AmortizedOpeningTooLarge(usize),
+}
+
+/// KZG10 structured reference string: `powers_of_g = [g, β·g, β²·g, ..., β^d·g]` in
+/// `G1`, and the `G2` side needed for the pairing check (`h`, `beta_h = β·h`), with
+/// `powers_of_h = [h, beta_h]` kept alongside for symmetry with `powers_of_g`.
+pub struct UniversalParams {
+    pub powers_of_g: Vec<G1Affine>,
+    pub powers_of_h: Vec<G2Affine>,
+    pub h: G2Affine,
+    pub beta_h: G2Affine,
+}
+
+impl UniversalParams {
+    /// Largest polynomial degree (and largest domain size for `open_on_domain`) this
+    /// SRS supports.
+    pub fn max_degree(&self) -> usize {
+        self.powers_of_g.len() - 1
+    }
+
+    /// Samples a fresh SRS for polynomials of degree `<= max_degree`. This samples its
+    /// own toxic waste `beta` and never zeroizes it, so it is only fit for tests: a real
+    /// deployment needs a trusted setup (or a multi-party ceremony) that no single party
+    /// ever reconstructs `beta` for.
+    pub fn setup<R: Rng + ?Sized>(max_degree: usize, rng: &mut R) -> Self {
+        let beta = F::rand(rng);
+        let g = G1Projective::generator();
+        let h = G2Projective::generator();
+
+        let mut powers_of_g = Vec::with_capacity(max_degree + 1);
+        let mut cur = g;
+        for _ in 0..=max_degree {
+            powers_of_g.push(cur.into_affine());
+            cur *= beta;
+        }
+
+        let h_affine = h.into_affine();
+        let beta_h = (h * beta).into_affine();
+
+        UniversalParams {
+            powers_of_g,
+            powers_of_h: vec![h_affine, beta_h],
+            h: h_affine,
+            beta_h,
+        }
+    }
+}
+
+/// Commits to `poly` as `Σ coeffs[i] · (β^i·g) = P(β)·g`, evaluated in `G1` without
+/// ever learning `β`.
+pub fn commit(params: &UniversalParams, poly: &Poly) -> Result<G1Affine, Error> {
+    let coeffs = poly.coeffs();
+    if coeffs.len() > params.powers_of_g.len() {
+        return Err(Error::PolynomialDegreeTooLarge {
+            degree: poly.degree(),
+            max_degree: params.max_degree(),
+        });
+    }
+
+    let mut acc = G1Projective::zero();
+    for (c, p) in coeffs.iter().zip(params.powers_of_g.iter()) {
+        acc += *p * *c;
+    }
+    Ok(acc.into_affine())
+}
+
+/// Opens `poly` at a single point `z`, returning `(value, witness)` where
+/// `witness = Commit((P(X) - value) / (X - z))`.
+pub fn open(params: &UniversalParams, poly: &Poly, z: F) -> Result<(F, G1Affine), Error> {
+    if poly.coeffs().len() > params.powers_of_g.len() {
+        return Err(Error::PolynomialDegreeTooLarge {
+            degree: poly.degree(),
+            max_degree: params.max_degree(),
+        });
+    }
+
+    let value = poly.evaluate(&z);
+    let quotient = shifted_quotient(poly.coeffs(), value, z);
+    let witness = commit(params, &Poly::from_coeffs(quotient))?;
+    Ok((value, witness))
+}
+
+/// Verifies `(value, witness)` was a correct opening of `commitment` at `z`:
+/// `e(commitment - value·g, h) == e(witness, beta_h - z·h)`, i.e.
+/// `e(Commit(P) - value·g, h) == e(Commit((P(X)-value)/(X-z)), Commit(X-z))`.
+pub fn verify(
+    params: &UniversalParams,
+    commitment: G1Affine,
+    z: F,
+    value: F,
+    witness: G1Affine,
+) -> bool {
+    let g = params.powers_of_g[0];
+    let lhs_g1 = (commitment.into_group() - g.into_group() * value).into_affine();
+    let rhs_g2 = (params.beta_h.into_group() - params.h.into_group() * z).into_affine();
+
+    Bls12_381::pairing(lhs_g1, params.h) == Bls12_381::pairing(witness, rhs_g2)
+}
+
+/// Opens `poly` at every point of `domain` at once. Evaluates `poly` on the whole
+/// domain in a single `evaluate_many` pass (reusing `domain`'s cached `omega_pows`
+/// rather than re-deriving each evaluation point), instead of calling `open` -- which
+/// would each redo its own evaluation of `poly` from scratch -- once per point.
+pub fn open_on_domain(
+    params: &UniversalParams,
+    poly: &Poly,
+    domain: &DomainH<F>,
+) -> Result<Vec<(F, G1Affine)>, Error> {
+    if poly.coeffs().len() > params.powers_of_g.len() {
+        return Err(Error::PolynomialDegreeTooLarge {
+            degree: poly.degree(),
+            max_degree: params.max_degree(),
+        });
+    }
+    if domain.n > params.max_degree() {
+        return Err(Error::AmortizedOpeningTooLarge(domain.n));
+    }
+
+    let values = poly.evaluate_many(&domain.omega_pows);
+
+    let mut openings = Vec::with_capacity(domain.n);
+    for (&z, &value) in domain.omega_pows.iter().zip(values.iter()) {
+        let quotient = shifted_quotient(poly.coeffs(), value, z);
+        let witness = commit(params, &Poly::from_coeffs(quotient))?;
+        openings.push((value, witness));
+    }
+    Ok(openings)
+}
+
+/// Synthetic division of `(P(X) - value) / (X - z)` for `P` given by ascending-order
+/// `coeffs`, returning the ascending-order quotient coefficients. `value` must be
+/// `P(z)` so the division is exact and the remainder is zero.
+fn shifted_quotient(coeffs: &[F], value: F, z: F) -> Vec<F> {
+    let mut shifted = coeffs.to_vec();
+    if shifted.is_empty() {
+        shifted.push(F::zero());
+    }
+    shifted[0] -= value;
+
+    let n = shifted.len();
+    if n <= 1 {
+        return vec![];
+    }
+    let mut quotient = vec![F::zero(); n - 1];
+    quotient[n - 2] = shifted[n - 1];
+    for i in (1..n - 1).rev() {
+        quotient[i - 1] = shifted[i] + z * quotient[i];
+    }
+    quotient
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn random_poly(deg: usize, rng: &mut StdRng) -> Poly {
+        Poly::from_coeffs((0..=deg).map(|_| F::rand(rng)).collect())
+    }
+
+    #[test]
+    fn commit_open_verify_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let params = UniversalParams::setup(8, &mut rng);
+        let poly = random_poly(5, &mut rng);
+
+        let commitment = commit(&params, &poly).unwrap();
+        let z = F::rand(&mut rng);
+        let (value, witness) = open(&params, &poly, z).unwrap();
+
+        assert_eq!(value, poly.evaluate(&z));
+        assert!(verify(&params, commitment, z, value, witness));
+    }
+
+    #[test]
+    fn verify_rejects_a_tampered_value() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let params = UniversalParams::setup(8, &mut rng);
+        let poly = random_poly(4, &mut rng);
+
+        let commitment = commit(&params, &poly).unwrap();
+        let z = F::rand(&mut rng);
+        let (value, witness) = open(&params, &poly, z).unwrap();
+
+        let wrong_value = value + F::from(1u64);
+        assert!(!verify(&params, commitment, z, wrong_value, witness));
+    }
+
+    #[test]
+    fn commit_rejects_a_polynomial_above_the_srs_degree() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let params = UniversalParams::setup(4, &mut rng);
+        let poly = random_poly(5, &mut rng);
+
+        assert_eq!(
+            commit(&params, &poly),
+            Err(Error::PolynomialDegreeTooLarge {
+                degree: 5,
+                max_degree: 4,
+            })
+        );
+    }
+
+    #[test]
+    fn open_on_domain_matches_individually_opening_each_point() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let params = UniversalParams::setup(16, &mut rng);
+        let poly = random_poly(6, &mut rng);
+        let domain = DomainH::<F>::new_radix2(8);
+
+        let batched = open_on_domain(&params, &poly, &domain).unwrap();
+        assert_eq!(batched.len(), 8);
+
+        for (j, &z) in domain.omega_pows.iter().enumerate() {
+            let (value, witness) = open(&params, &poly, z).unwrap();
+            assert_eq!(batched[j], (value, witness));
+        }
+    }
+
+    #[test]
+    fn open_on_domain_rejects_a_domain_larger_than_the_srs() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let params = UniversalParams::setup(4, &mut rng);
+        let poly = random_poly(3, &mut rng);
+        let domain = DomainH::<F>::new_radix2(8);
+
+        assert_eq!(
+            open_on_domain(&params, &poly, &domain),
+            Err(Error::AmortizedOpeningTooLarge(8))
+        );
+    }
+}
@@ -1,7 +1,6 @@
 //! poly crate: thin helpers around ark_poly 0.5.x univariate dense polynomials.
 
-use ark_bls12_381::Fr as F;
-use ark_ff::{One, Zero};
+use ark_ff::{FftField, One, Zero};
 use ark_poly::{
     univariate::DensePolynomial,
     DenseUVPolynomial, // provides constructors like from_coefficients_vec
@@ -11,15 +10,26 @@ use ark_poly::{
 #[cfg(feature = "serde1")]
 use serde::{Deserialize, Serialize};
 
-/// A wrapper around a DensePolynomial<F> with optional serde derives for your own types.
+pub mod kzg;
+
+/// BLS12-381's scalar field, the field `Poly` was originally hard-wired to; kept as the
+/// default type parameter below so every pre-existing `Poly` (no turbofish) call site
+/// keeps resolving to exactly the same concrete type.
+pub type Bls12_381Fr = ark_bls12_381::Fr;
+
+/// A wrapper around a `DensePolynomial<F>` with optional serde derives for your own
+/// types, generic over any `F: FftField` (a radix-2 two-adic subgroup) so the same
+/// merge/eval code paths can be reused across e.g. the BLS12-381 field (KZG) and the
+/// Pallas field (FRI/Halo-style), rather than duplicating this wrapper per field.
+/// Defaults to [`Bls12_381Fr`], matching every existing unparameterized use of `Poly`.
 /// Note: Field elements should be serialized canonically via ark_serialize if needed.
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, Default)]
-pub struct Poly {
+pub struct Poly<F: FftField = Bls12_381Fr> {
     pub poly: DensePolynomial<F>,
 }
 
-impl Poly {
+impl<F: FftField> Poly<F> {
     /// Construct from coefficients in ascending order: coeffs[0] + coeffs[1] X + ...
     pub fn from_coeffs(coeffs: Vec<F>) -> Self {
         let p = DensePolynomial::from_coefficients_vec(coeffs);
@@ -62,42 +72,42 @@ impl Poly {
     }
 
     /// Add another polynomial (by value).
-    pub fn add(&self, other: &Poly) -> Poly {
+    pub fn add(&self, other: &Poly<F>) -> Poly<F> {
         Poly {
             poly: &self.poly + &other.poly,
         }
     }
 
     /// Multiply by another polynomial.
-    pub fn mul(&self, other: &Poly) -> Poly {
+    pub fn mul(&self, other: &Poly<F>) -> Poly<F> {
         Poly {
             poly: &self.poly * &other.poly,
         }
     }
 
     /// Scale by a field element.
-    pub fn scale(&self, c: F) -> Poly {
+    pub fn scale(&self, c: F) -> Poly<F> {
         Poly {
             poly: self.poly.clone() * c,
         }
     }
 
     /// Construct the zero polynomial.
-    pub fn zero() -> Poly {
+    pub fn zero() -> Poly<F> {
         Poly {
             poly: DensePolynomial::from_coefficients_vec(vec![]),
         }
     }
 
     /// Construct the constant polynomial c.
-    pub fn constant(c: F) -> Poly {
+    pub fn constant(c: F) -> Poly<F> {
         Poly {
             poly: DensePolynomial::from_coefficients_vec(vec![c]),
         }
     }
 
     /// Construct X (i.e., 0 + 1*X).
-    pub fn monomial_x() -> Poly {
+    pub fn monomial_x() -> Poly<F> {
         Poly {
             poly: DensePolynomial::from_coefficients_vec(vec![F::zero(), F::one()]),
         }
@@ -108,6 +118,8 @@ impl Poly {
 mod tests {
     use super::*;
 
+    type F = Bls12_381Fr;
+
     #[test]
     fn construct_and_eval() {
         // p(x) = 3 + 2x + x^2
@@ -135,7 +147,7 @@ mod tests {
 
     #[test]
     fn constants_and_x() {
-        let z = Poly::zero();
+        let z: Poly<F> = Poly::zero();
         assert_eq!(z.degree(), 0);
         assert_eq!(z.degree_opt(), None);
 
@@ -143,7 +155,7 @@ mod tests {
         assert_eq!(c.degree(), 0);
         assert_eq!(c.evaluate(&F::from(10u64)), F::from(7u64));
 
-        let x = Poly::monomial_x();
+        let x: Poly<F> = Poly::monomial_x();
         assert_eq!(x.degree(), 1);
         assert_eq!(x.evaluate(&F::from(3u64)), F::from(3u64));
     }
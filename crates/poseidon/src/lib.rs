@@ -13,11 +13,29 @@ pub const RP: usize = 64;      // number of partial rounds (t=17)
 pub const RP_9: usize = 60;
 pub const ALPHA: u64 = 5;      // S-box x^5
 
+/// Poseidon parameters for a permutation of width `W` with `NP` partial rounds,
+/// monomorphized per `(W, NP)` so `permute_generic` runs end to end on stack-allocated
+/// arrays -- no heap allocation on the hot path, unlike `PoseidonParamsDynamic`'s
+/// `Vec`-backed `mds_mul_dynamic_in_place`. `PoseidonParams` (the type the rest of
+/// this crate and its callers already use) is just `PoseidonParamsGeneric<T, RP>`.
 #[derive(Clone)]
-pub struct PoseidonParams {
-    pub mds: [[F; T]; T],
-    pub rc_full: [[F; T]; RF],
-    pub rc_partial: [F; RP],
+pub struct PoseidonParamsGeneric<const W: usize, const NP: usize> {
+    pub mds: [[F; W]; W],
+    pub rc_full: [[F; W]; RF],
+    pub rc_partial: [F; NP],
+}
+
+pub type PoseidonParams = PoseidonParamsGeneric<T, RP>;
+
+/// The number of partial rounds for a supported width, i.e. the small table
+/// `poseidon_params_for_width` already matches on, exposed so const-generic callers
+/// can look up the `NP` to instantiate `PoseidonParamsGeneric<W, NP>` with.
+pub const fn rounds_partial_for_width(w: usize) -> usize {
+    match w {
+        17 => RP,
+        9 => RP_9,
+        _ => panic!("unsupported Poseidon width; supported w ∈ {{17, 9}}"),
+    }
 }
 
 #[inline]
@@ -28,51 +46,57 @@ pub fn sbox5(x: F) -> F {
     x * x4
 }
 
-pub fn permute(state: &mut [F; T], params: &PoseidonParams) {
+/// The permutation, generic over width `W` and partial-round count `NP`; `permute`
+/// (width `T = 17`) is the monomorphization callers already use.
+pub fn permute_generic<const W: usize, const NP: usize>(state: &mut [F; W], params: &PoseidonParamsGeneric<W, NP>) {
     let rf_half = RF / 2;
 
     // First half full rounds
     for r in 0..rf_half {
         // Add round constants (ARK)
-        for i in 0..T {
+        for i in 0..W {
             state[i] += params.rc_full[r][i];
         }
         // Full S-box layer
-        for i in 0..T {
+        for i in 0..W {
             state[i] = sbox5(state[i]);
         }
         // MDS linear layer
-        *state = mds_mul_fixed(&params.mds, state);
+        *state = mds_mul_generic(&params.mds, state);
     }
 
     // Partial rounds
-    for r in 0..RP {
+    for r in 0..NP {
         // ARK on first element
         state[0] += params.rc_partial[r];
         // S-box on first element
         state[0] = sbox5(state[0]);
         // MDS
-        *state = mds_mul_fixed(&params.mds, state);
+        *state = mds_mul_generic(&params.mds, state);
     }
 
     // Second half full rounds
     for r in rf_half..RF {
-        for i in 0..T {
+        for i in 0..W {
             state[i] += params.rc_full[r][i];
         }
-        for i in 0..T {
+        for i in 0..W {
             state[i] = sbox5(state[i]);
         }
-        *state = mds_mul_fixed(&params.mds, state);
+        *state = mds_mul_generic(&params.mds, state);
     }
 }
 
-// Multiply state vector by MDS matrix: out = M * state (fixed T)
-fn mds_mul_fixed(mds: &[[F; T]; T], state: &[F; T]) -> [F; T] {
-    let mut out = [F::zero(); T];
-    for i in 0..T {
+pub fn permute(state: &mut [F; T], params: &PoseidonParams) {
+    permute_generic(state, params)
+}
+
+// Multiply state vector by MDS matrix: out = M * state (generic W)
+fn mds_mul_generic<const W: usize>(mds: &[[F; W]; W], state: &[F; W]) -> [F; W] {
+    let mut out = [F::zero(); W];
+    for i in 0..W {
         let mut acc = F::zero();
-        for j in 0..T {
+        for j in 0..W {
             acc += mds[i][j] * state[j];
         }
         out[i] = acc;
@@ -101,6 +125,10 @@ pub fn hash_with_ds(inputs: &[F], ds_tag: F, params: &PoseidonParams) -> F {
 
 // ========= Milestone 1 additions: dynamic width support and params builder =========
 
+/// Heap-backed Poseidon parameters for a width chosen at runtime (e.g. picked by
+/// Merkle arity). Kept alongside `PoseidonParamsGeneric` for exactly that case;
+/// callers who know their width at compile time should prefer the const-generic
+/// path so `permute_generic` avoids `mds_mul_dynamic_in_place`'s `Vec` allocations.
 #[derive(Clone, Debug)]
 pub struct PoseidonParamsDynamic {
     pub t: usize,                 // state width
@@ -111,12 +139,26 @@ pub struct PoseidonParamsDynamic {
     pub mds: Vec<Vec<F>>,         // t x t
     pub rc_full: Vec<Vec<F>>,     // RF x t
     pub rc_partial: Vec<F>,       // RP elements
+    /// The dense `t x t` matrix applied once, immediately before the partial-round
+    /// loop, that absorbs the lane-mixing `partial_sparse_mds` would otherwise repeat
+    /// every round. See `build_partial_sparse_mds`.
+    pub pre_sparse_mds: Vec<Vec<F>>,
+    /// One `(first_row, first_col)` pair per partial round: `first_row` has `t`
+    /// entries (row 0 of the round's sparse matrix) and `first_col` has `t - 1`
+    /// entries (column 0 for state indices `1..t`, the rest of the matrix being
+    /// the identity). Composing `pre_sparse_mds` with these reproduces the effect
+    /// of applying the dense `mds` every partial round, in O(t) per round instead
+    /// of O(t^2). See `build_partial_sparse_mds`.
+    pub partial_sparse_mds: Vec<(Vec<F>, Vec<F>)>,
 }
 
 /// Build Poseidon parameters for width t with alpha=5, RF=8, RP in {64,60}.
 /// Supported widths: t = 17 (m=16), t = 9 (m=8).
-/// Uses deterministic fr_from_hash-based derivation for stability.
-/// Swap in audited constants when ready without changing the signature.
+/// Round constants come from the Grain LFSR generator described in the Poseidon
+/// paper (see the `grain` module) applied to this field/width; only internal
+/// determinism is tested here (`grain_round_constants_are_deterministic_and_right_shaped`),
+/// not agreement with any other implementation's constants for this parameter set.
+/// The MDS matrix is still `fr_from_hash`-derived scaffolding.
 pub fn poseidon_params_for_width(t: usize) -> PoseidonParamsDynamic {
     let (rf, rp) = match t {
         17 => (8usize, 64usize),
@@ -127,8 +169,8 @@ pub fn poseidon_params_for_width(t: usize) -> PoseidonParamsDynamic {
     let seed = seed_for_t(t);
 
     let mds = derive_mds(&seed, t);
-    let rc_full = derive_rc_full(&seed, rf, t);
-    let rc_partial = derive_rc_partial(&seed, rp);
+    let (rc_full, rc_partial) = grain::generate_round_constants(t, rf, rp);
+    let (pre_sparse_mds, partial_sparse_mds) = build_partial_sparse_mds(&mds, rp);
 
     PoseidonParamsDynamic {
         t,
@@ -139,7 +181,37 @@ pub fn poseidon_params_for_width(t: usize) -> PoseidonParamsDynamic {
         mds,
         rc_full,
         rc_partial,
+        pre_sparse_mds,
+        partial_sparse_mds,
+    }
+}
+
+/// Build const-generic Poseidon parameters for width `W` with `NP` partial rounds,
+/// from the same Grain/Cauchy derivation `poseidon_params_for_width` uses -- the
+/// monomorphized counterpart for callers who know their width at compile time (e.g.
+/// `poseidon_params_generic::<17, RP>()` or `::<9, RP_9>()`) and want `permute_generic`
+/// to run without `PoseidonParamsDynamic`'s heap allocations.
+/// Panics if `NP != rounds_partial_for_width(W)`.
+pub fn poseidon_params_generic<const W: usize, const NP: usize>() -> PoseidonParamsGeneric<W, NP> {
+    assert_eq!(NP, rounds_partial_for_width(W), "NP must match the width's partial-round count");
+    let seed = seed_for_t(W);
+
+    let mds_vec = derive_mds(&seed, W);
+    let (rc_full_vec, rc_partial_vec) = grain::generate_round_constants(W, RF, NP);
+
+    let mut mds = [[F::zero(); W]; W];
+    for (i, row) in mds_vec.into_iter().enumerate() {
+        mds[i] = row.try_into().expect("derive_mds returns W-length rows");
+    }
+    let mut rc_full = [[F::zero(); W]; RF];
+    for (r, row) in rc_full_vec.into_iter().enumerate() {
+        rc_full[r] = row.try_into().expect("generate_round_constants returns W-length rows");
     }
+    let rc_partial: [F; NP] = rc_partial_vec
+        .try_into()
+        .unwrap_or_else(|_| panic!("generate_round_constants returns NP partial-round constants"));
+
+    PoseidonParamsGeneric { mds, rc_full, rc_partial }
 }
 
 fn seed_for_t(t: usize) -> Vec<u8> {
@@ -150,46 +222,344 @@ fn seed_for_t(t: usize) -> Vec<u8> {
     s
 }
 
+/// Builds the MDS matrix as a Cauchy matrix: `mds[i][j] = (x_i + y_j)^{-1}` for `2t`
+/// deterministically drawn field elements with all `x_i` distinct, all `y_j`
+/// distinct, and every `x_i + y_j != 0`. Unlike the old independent-hash-per-cell
+/// construction, a Cauchy matrix is invertible (and MDS) by construction -- every
+/// square submatrix has a nonzero determinant, so the linear layer can't silently
+/// degenerate.
 fn derive_mds(seed: &[u8], t: usize) -> Vec<Vec<F>> {
-    let mut m = vec![vec![F::zero(); t]; t];
+    let xs = draw_distinct_fr(seed, "POSEIDON-MDS-X", t, &[]);
+    let ys = draw_distinct_fr(seed, "POSEIDON-MDS-Y", t, &xs);
+
+    let mut mds = vec![vec![F::zero(); t]; t];
     for i in 0..t {
         for j in 0..t {
-            let tag = "POSEIDON-MDS";
-            let mut data = Vec::with_capacity(seed.len() + 16);
-            data.extend_from_slice(&(i as u64).to_le_bytes());
-            data.extend_from_slice(&(j as u64).to_le_bytes());
-            data.extend_from_slice(seed);
-            m[i][j] = fr_from_hash(tag, &data);
+            let denom = xs[i] + ys[j];
+            mds[i][j] = denom.inverse().expect("x_i + y_j != 0 is enforced by draw_distinct_fr");
         }
     }
+    debug_assert!(is_invertible(&mds), "a Cauchy matrix must be invertible");
+    mds
+}
+
+/// Draws `count` distinct field elements by hashing an incrementing counter,
+/// skipping any draw already in the output set or whose sum with any element of
+/// `avoid_sum_zero_with` is zero (used to keep the Cauchy `x`/`y` sets disjoint in
+/// that sense).
+fn draw_distinct_fr(seed: &[u8], tag: &str, count: usize, avoid_sum_zero_with: &[F]) -> Vec<F> {
+    let mut out = Vec::with_capacity(count);
+    let mut counter: u64 = 0;
+    while out.len() < count {
+        let mut data = Vec::with_capacity(seed.len() + 8);
+        data.extend_from_slice(&counter.to_le_bytes());
+        data.extend_from_slice(seed);
+        let candidate = fr_from_hash(tag, &data);
+        counter += 1;
+
+        if out.contains(&candidate) {
+            continue;
+        }
+        if avoid_sum_zero_with.iter().any(|&x| (x + candidate).is_zero()) {
+            continue;
+        }
+        out.push(candidate);
+    }
+    out
+}
+
+/// Determinant of a square matrix via Gaussian elimination with partial pivoting;
+/// `None` means the matrix is singular.
+fn determinant(m: &[Vec<F>]) -> Option<F> {
+    let n = m.len();
+    let mut a: Vec<Vec<F>> = m.to_vec();
+    let mut det = F::one();
+    for col in 0..n {
+        let pivot_row = (col..n).find(|&r| !a[r][col].is_zero())?;
+        if pivot_row != col {
+            a.swap(col, pivot_row);
+            det = -det;
+        }
+        det *= a[col][col];
+        let pivot_inv = a[col][col].inverse().expect("pivot is nonzero by construction");
+        for row in (col + 1)..n {
+            let factor = a[row][col] * pivot_inv;
+            if factor.is_zero() {
+                continue;
+            }
+            for k in col..n {
+                let delta = a[col][k] * factor;
+                a[row][k] -= delta;
+            }
+        }
+    }
+    Some(det)
+}
+
+fn is_invertible(m: &[Vec<F>]) -> bool {
+    determinant(m).is_some()
+}
+
+/// Factors the partial-round segment's repeated dense MDS multiply into one dense
+/// matrix applied up front (`pre_sparse_mds`) plus, per round, a sparse matrix with
+/// only its first row and first column dense (the rest identity) -- the standard
+/// Poseidon optimization, since the S-box only ever touches lane 0 during partial
+/// rounds, so the mixing of lanes `1..t` with each other can be pushed entirely into
+/// a single upfront transform.
+///
+/// Writing `mds` in block form `[[m00, m_top], [m_left, m_b]]` (splitting off row/
+/// column 0), the round-`r` sparse matrix's first row is `m_top` pre-multiplied by
+/// `(m_b^{rp-r})^{-1}` and its first column is `m_left` pre-multiplied by
+/// `m_b^{rp-r-1}`; `pre_sparse_mds` embeds `m_b^rp` in its bottom-right block. This
+/// keeps the lane-0 S-box input identical to the fully dense permutation at every
+/// round while deferring all `m_b`-mixing to the one upfront multiply -- `m_b` is
+/// invertible because `mds` is MDS (every square submatrix is nonsingular), so the
+/// construction never panics on the matrices this module derives.
+fn build_partial_sparse_mds(mds: &[Vec<F>], rounds_partial: usize) -> (Vec<Vec<F>>, Vec<(Vec<F>, Vec<F>)>) {
+    let t = mds.len();
+    let dim = t - 1;
+
+    let m00 = mds[0][0];
+    let m_top: Vec<F> = mds[0][1..].to_vec();
+    let m_left: Vec<F> = (1..t).map(|i| mds[i][0]).collect();
+    let m_b: Vec<Vec<F>> = (1..t).map(|i| mds[i][1..].to_vec()).collect();
+    let m_b_inv = invert_matrix(&m_b);
+
+    // a_hat / a_hat_inv track m_b^{rounds_partial - r} and its inverse, starting
+    // from the identity at r = rounds_partial and working backwards to r = 0.
+    let mut a_hat = identity_matrix(dim);
+    let mut a_hat_inv = identity_matrix(dim);
+
+    let mut sparse = vec![(Vec::new(), Vec::new()); rounds_partial];
+    for r in (0..rounds_partial).rev() {
+        let v_r = mat_vec_mul(&a_hat, &m_left);
+        a_hat = mat_mul(&a_hat, &m_b);
+        a_hat_inv = mat_mul(&m_b_inv, &a_hat_inv);
+        let w_r = vec_mat_mul(&m_top, &a_hat_inv);
+
+        let mut first_row = Vec::with_capacity(t);
+        first_row.push(m00);
+        first_row.extend(w_r);
+        sparse[r] = (first_row, v_r);
+    }
+
+    let mut pre_sparse_mds = identity_matrix(t);
+    for i in 0..dim {
+        for j in 0..dim {
+            pre_sparse_mds[1 + i][1 + j] = a_hat[i][j];
+        }
+    }
+
+    (pre_sparse_mds, sparse)
+}
+
+fn identity_matrix(n: usize) -> Vec<Vec<F>> {
+    let mut m = vec![vec![F::zero(); n]; n];
+    for (i, row) in m.iter_mut().enumerate() {
+        row[i] = F::one();
+    }
     m
 }
 
-fn derive_rc_full(seed: &[u8], rf: usize, t: usize) -> Vec<Vec<F>> {
-    let mut rc = vec![vec![F::zero(); t]; rf];
-    for r in 0..rf {
-        for i in 0..t {
-            let tag = "POSEIDON-RC-FULL";
-            let mut data = Vec::with_capacity(seed.len() + 16);
-            data.extend_from_slice(&(r as u64).to_le_bytes());
-            data.extend_from_slice(&(i as u64).to_le_bytes());
-            data.extend_from_slice(seed);
-            rc[r][i] = fr_from_hash(tag, &data);
+fn mat_mul(a: &[Vec<F>], b: &[Vec<F>]) -> Vec<Vec<F>> {
+    let n = a.len();
+    let mut out = vec![vec![F::zero(); n]; n];
+    for i in 0..n {
+        for k in 0..n {
+            if a[i][k].is_zero() {
+                continue;
+            }
+            for j in 0..n {
+                out[i][j] += a[i][k] * b[k][j];
+            }
         }
     }
-    rc
+    out
 }
 
-fn derive_rc_partial(seed: &[u8], rp: usize) -> Vec<F> {
-    let mut rc = vec![F::zero(); rp];
-    for r in 0..rp {
-        let tag = "POSEIDON-RC-PART";
-        let mut data = Vec::with_capacity(seed.len() + 8);
-        data.extend_from_slice(&(r as u64).to_le_bytes());
-        data.extend_from_slice(seed);
-        rc[r] = fr_from_hash(tag, &data);
+fn mat_vec_mul(a: &[Vec<F>], v: &[F]) -> Vec<F> {
+    let n = a.len();
+    let mut out = vec![F::zero(); n];
+    for i in 0..n {
+        let mut acc = F::zero();
+        for j in 0..n {
+            acc += a[i][j] * v[j];
+        }
+        out[i] = acc;
+    }
+    out
+}
+
+/// Row-vector times matrix: `out[j] = sum_i v[i] * a[i][j]`.
+fn vec_mat_mul(v: &[F], a: &[Vec<F>]) -> Vec<F> {
+    let n = a.len();
+    let mut out = vec![F::zero(); n];
+    for j in 0..n {
+        let mut acc = F::zero();
+        for i in 0..n {
+            acc += v[i] * a[i][j];
+        }
+        out[j] = acc;
+    }
+    out
+}
+
+/// Inverts a square matrix via Gauss-Jordan elimination with partial pivoting.
+/// Panics if `m` is singular; callers in this module only ever invert MDS
+/// submatrices, which are nonsingular by construction.
+fn invert_matrix(m: &[Vec<F>]) -> Vec<Vec<F>> {
+    let n = m.len();
+    let mut a: Vec<Vec<F>> = m.to_vec();
+    let mut inv = identity_matrix(n);
+    for col in 0..n {
+        let pivot_row = (col..n)
+            .find(|&r| !a[r][col].is_zero())
+            .expect("matrix is invertible by construction");
+        if pivot_row != col {
+            a.swap(col, pivot_row);
+            inv.swap(col, pivot_row);
+        }
+        let pivot_inv = a[col][col].inverse().expect("pivot is nonzero by construction");
+        for k in 0..n {
+            a[col][k] *= pivot_inv;
+            inv[col][k] *= pivot_inv;
+        }
+        for row in 0..n {
+            if row == col {
+                continue;
+            }
+            let factor = a[row][col];
+            if factor.is_zero() {
+                continue;
+            }
+            for k in 0..n {
+                let delta_a = a[col][k] * factor;
+                a[row][k] -= delta_a;
+                let delta_inv = inv[col][k] * factor;
+                inv[row][k] -= delta_inv;
+            }
+        }
+    }
+    inv
+}
+
+/// Spec-conformant Poseidon round-constant generation via the Grain LFSR, replacing
+/// the `fr_from_hash`-based scaffolding the round constants used to share with the
+/// MDS matrix. See the reference Poseidon parameter-generation script: an 80-bit
+/// shift register is seeded from the field/permutation description, warmed up, then
+/// clocked to produce whitened bits that are rejection-sampled into field elements.
+pub mod grain {
+    use super::F;
+    use ark_ff::{BigInteger, PrimeField};
+
+    /// The 80-bit Grain-style LFSR used only to derive round constants.
+    struct Grain {
+        state: [u8; 80],
+    }
+
+    impl Grain {
+        /// Packs the 80-bit initial state: 2 bits field type (`0b01` = prime
+        /// field), 4 bits S-box exponent (`alpha = 5`), 12 bits `ceil(log2(p))`,
+        /// 12 bits `t`, 10 bits `rounds_full`, 10 bits `rounds_partial`, with the
+        /// remaining bits set to 1 -- then clocks 160 times discarding output to
+        /// mix the seed in before any constants are drawn.
+        fn new(modulus_bits: u32, t: usize, rounds_full: usize, rounds_partial: usize) -> Self {
+            let mut bits = Vec::with_capacity(80);
+            push_bits(&mut bits, 0b01, 2);
+            push_bits(&mut bits, 5, 4); // S-box exponent alpha = 5
+            push_bits(&mut bits, modulus_bits as u64, 12);
+            push_bits(&mut bits, t as u64, 12);
+            push_bits(&mut bits, rounds_full as u64, 10);
+            push_bits(&mut bits, rounds_partial as u64, 10);
+            bits.resize(80, 1);
+
+            let mut state = [0u8; 80];
+            state.copy_from_slice(&bits);
+            let mut grain = Grain { state };
+            for _ in 0..160 {
+                grain.clock();
+            }
+            grain
+        }
+
+        /// Clocks the register once: emits the bit shifted out of position 0, and
+        /// feeds back `b_{i+80} = b_{i+62} ^ b_{i+51} ^ b_{i+38} ^ b_{i+23} ^ b_{i+13} ^ b_i`.
+        fn clock(&mut self) -> u8 {
+            let b0 = self.state[0];
+            let feedback = self.state[62]
+                ^ self.state[51]
+                ^ self.state[38]
+                ^ self.state[23]
+                ^ self.state[13]
+                ^ self.state[0];
+            self.state.copy_within(1..80, 0);
+            self.state[79] = feedback;
+            b0
+        }
+
+        /// One whitened output bit: a `0` clock is followed by a discarded clock
+        /// and another attempt; a `1` clock is followed by the clock whose bit is
+        /// the one actually emitted -- so only every other accepted bit is used.
+        fn next_bit(&mut self) -> u8 {
+            loop {
+                if self.clock() == 0 {
+                    self.clock();
+                    continue;
+                }
+                return self.clock();
+            }
+        }
+    }
+
+    fn push_bits(bits: &mut Vec<u8>, value: u64, width: u32) {
+        for i in (0..width).rev() {
+            bits.push(((value >> i) & 1) as u8);
+        }
+    }
+
+    /// The bit-length of the field modulus, i.e. `ceil(log2(p))`.
+    fn modulus_bit_len() -> u32 {
+        let bits = F::MODULUS.to_bits_be();
+        let leading_zeros = bits.iter().take_while(|b| !**b).count();
+        (bits.len() - leading_zeros) as u32
+    }
+
+    /// Draws one rejection-sampled field element: assembles `modulus_bits` whitened
+    /// LFSR bits into a candidate, discarding and regenerating whenever it's >= p.
+    fn next_field_element(grain: &mut Grain, modulus_bits: u32) -> F {
+        loop {
+            let bits: Vec<bool> = (0..modulus_bits).map(|_| grain.next_bit() == 1).collect();
+            let candidate = <F as PrimeField>::BigInt::from_bits_be(&bits);
+            if candidate < F::MODULUS {
+                return F::from_bigint(candidate).expect("candidate is below the modulus by construction");
+            }
+        }
+    }
+
+    /// Generates all `rounds_full * t + rounds_partial` round constants for a
+    /// permutation of width `t`, in the order the permutation actually consumes
+    /// them: the first `rounds_full/2` full rounds, then the `rounds_partial`
+    /// partial rounds, then the remaining full rounds -- returned split the same
+    /// way `PoseidonParamsDynamic` stores them (`rc_full` rows of `t`, flat
+    /// `rc_partial`).
+    pub fn generate_round_constants(t: usize, rounds_full: usize, rounds_partial: usize) -> (Vec<Vec<F>>, Vec<F>) {
+        let modulus_bits = modulus_bit_len();
+        let mut grain = Grain::new(modulus_bits, t, rounds_full, rounds_partial);
+
+        let rf_half = rounds_full / 2;
+        let mut rc_full = Vec::with_capacity(rounds_full);
+        for _ in 0..rf_half {
+            rc_full.push((0..t).map(|_| next_field_element(&mut grain, modulus_bits)).collect());
+        }
+        let rc_partial: Vec<F> = (0..rounds_partial)
+            .map(|_| next_field_element(&mut grain, modulus_bits))
+            .collect();
+        for _ in rf_half..rounds_full {
+            rc_full.push((0..t).map(|_| next_field_element(&mut grain, modulus_bits)).collect());
+        }
+
+        (rc_full, rc_partial)
     }
-    rc
 }
 
 /// Generic permutation for dynamic params (t ∈ {9, 17}).
@@ -215,11 +585,14 @@ pub fn permute_dynamic(state: &mut [F], params: &PoseidonParamsDynamic) {
         mds_mul_dynamic_in_place(&params.mds, state);
     }
 
-    // Partial rounds (S-box on lane 0)
+    // Partial rounds (S-box on lane 0), via the sparse-MDS factorization: one
+    // dense multiply up front, then an O(t) sparse update per round instead of
+    // the O(t^2) dense `mds` multiply. See `build_partial_sparse_mds`.
+    mds_mul_dynamic_in_place(&params.pre_sparse_mds, state);
     for r in 0..rp {
         state[0] += params.rc_partial[r];
         state[0] = sbox5(state[0]);
-        mds_mul_dynamic_in_place(&params.mds, state);
+        apply_partial_sparse_mds(&params.partial_sparse_mds[r], state);
     }
 
     // Second half full rounds
@@ -234,6 +607,23 @@ pub fn permute_dynamic(state: &mut [F], params: &PoseidonParamsDynamic) {
     }
 }
 
+/// Applies one partial round's sparse matrix in place: O(t) instead of the O(t^2)
+/// dense `mds_mul_dynamic_in_place`, since every entry off the first row/column is
+/// the identity. `first_row` has `t` entries (row 0); `first_col[i - 1]` is row `i`'s
+/// only off-diagonal entry (column 0) for `i` in `1..t`.
+fn apply_partial_sparse_mds(sparse: &(Vec<F>, Vec<F>), state: &mut [F]) {
+    let (first_row, first_col) = sparse;
+    let t = state.len();
+    let mut new0 = F::zero();
+    for j in 0..t {
+        new0 += first_row[j] * state[j];
+    }
+    for i in 1..t {
+        state[i] += first_col[i - 1] * state[0];
+    }
+    state[0] = new0;
+}
+
 fn mds_mul_dynamic_in_place(mds: &[Vec<F>], state: &mut [F]) {
     let t = state.len();
     debug_assert_eq!(mds.len(), t);
@@ -259,33 +649,94 @@ fn absorb_one(x: F, state: &mut [F], cursor: &mut usize, rate: usize, params: &P
     }
 }
 
-/// DS-friendly hash for dynamic widths (rate = t-1, capacity=1).
-/// Absorbs ds_fields first, then inputs (children) in order, padding with 1 then 0s.
-/// Returns state[0] as the digest.
-pub fn hash_with_ds_dynamic(ds_fields: &[F], inputs: &[F], params: &PoseidonParamsDynamic) -> F {
-    let t = params.t;
-    let rate = params.rate;
-    assert_eq!(rate + 1, t);
+/// Which direction a `Sponge` last moved data: absorbing input in, or squeezing
+/// output out. An absorb right after a squeeze needs a fresh permutation first, the
+/// same rule any duplex sponge construction follows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SpongePhase {
+    Absorbing,
+    Squeezing,
+}
 
-    let mut state = vec![F::zero(); t];
-    let mut cursor = 0usize;
+/// A general-purpose duplex sponge over `PoseidonParamsDynamic`, for callers that
+/// need more than the single output lane `hash_with_ds_dynamic` squeezes -- e.g. a
+/// wide Fiat-Shamir challenge split across several field lanes, or a stream of them.
+pub struct Sponge<'a> {
+    state: Vec<F>,
+    cursor: usize,
+    phase: SpongePhase,
+    params: &'a PoseidonParamsDynamic,
+}
 
-    // Absorb DS preamble
-    for &x in ds_fields {
-        absorb_one(x, &mut state, &mut cursor, rate, params);
+impl<'a> Sponge<'a> {
+    pub fn new(params: &'a PoseidonParamsDynamic) -> Self {
+        Sponge {
+            state: vec![F::zero(); params.t],
+            cursor: 0,
+            phase: SpongePhase::Absorbing,
+            params,
+        }
     }
-    // Absorb message/children
-    for &x in inputs {
-        absorb_one(x, &mut state, &mut cursor, rate, params);
+
+    /// Absorbs `xs` into the rate, permuting whenever it fills. If the sponge was
+    /// last squeezing, permutes first so the already-read output isn't reused as
+    /// absorbed state.
+    pub fn absorb(&mut self, xs: &[F]) {
+        if self.phase == SpongePhase::Squeezing {
+            permute_dynamic(&mut self.state, self.params);
+            self.cursor = 0;
+            self.phase = SpongePhase::Absorbing;
+        }
+        for &x in xs {
+            absorb_one(x, &mut self.state, &mut self.cursor, self.params.rate, self.params);
+        }
     }
-    // Padding: 1 then zeros until block boundary
-    absorb_one(F::from(1u64), &mut state, &mut cursor, rate, params);
-    while cursor != 0 {
-        absorb_one(F::zero(), &mut state, &mut cursor, rate, params);
+
+    /// Reads `n` field elements, starting from lane 0 of a fresh permutation when
+    /// input has been absorbed since the last permutation, and permuting again
+    /// whenever more than `rate` outputs are requested in one call (or across calls,
+    /// once `squeeze` has advanced the cursor to the rate boundary).
+    pub fn squeeze(&mut self, n: usize) -> Vec<F> {
+        if self.phase == SpongePhase::Absorbing && self.cursor != 0 {
+            permute_dynamic(&mut self.state, self.params);
+            self.cursor = 0;
+        }
+        self.phase = SpongePhase::Squeezing;
+        let rate = self.params.rate;
+        let mut out = Vec::with_capacity(n);
+        while out.len() < n {
+            if self.cursor == rate {
+                permute_dynamic(&mut self.state, self.params);
+                self.cursor = 0;
+            }
+            out.push(self.state[self.cursor]);
+            self.cursor += 1;
+        }
+        out
     }
 
-    // Squeeze first element
-    state[0]
+    /// Absorbs `1` then `0`s until the next rate-block boundary -- the fixed
+    /// padding `hash_with_ds_dynamic` uses to separate message length from content.
+    fn pad_to_boundary(&mut self) {
+        self.absorb(&[F::from(1u64)]);
+        while self.cursor != 0 {
+            self.absorb(&[F::zero()]);
+        }
+    }
+}
+
+/// DS-friendly hash for dynamic widths (rate = t-1, capacity=1).
+/// Absorbs ds_fields first, then inputs (children) in order, padding with 1 then 0s.
+/// Returns the first squeezed element as the digest. A thin `Sponge` wrapper kept for
+/// callers who only need one output lane; use `Sponge` directly for more.
+pub fn hash_with_ds_dynamic(ds_fields: &[F], inputs: &[F], params: &PoseidonParamsDynamic) -> F {
+    assert_eq!(params.rate + 1, params.t);
+
+    let mut sponge = Sponge::new(params);
+    sponge.absorb(ds_fields);
+    sponge.absorb(inputs);
+    sponge.pad_to_boundary();
+    sponge.squeeze(1)[0]
 }
 
 // Parameter generation using utils::fr_from_hash for reproducible constants.
@@ -364,6 +815,8 @@ impl From<&PoseidonParams> for PoseidonParamsDynamic {
             rc_partial_v[r] = p.rc_partial[r];
         }
 
+        let (pre_sparse_mds, partial_sparse_mds) = build_partial_sparse_mds(&mds_v, rp);
+
         PoseidonParamsDynamic {
             t,
             rate,
@@ -373,6 +826,8 @@ impl From<&PoseidonParams> for PoseidonParamsDynamic {
             mds: mds_v,
             rc_full: rc_full_v,
             rc_partial: rc_partial_v,
+            pre_sparse_mds,
+            partial_sparse_mds,
         }
     }
 }
@@ -429,4 +884,203 @@ mod tests {
         assert_eq!(p_back.rc_full[0][0], p_static.rc_full[0][0]);
         assert_eq!(p_back.rc_partial[0], p_static.rc_partial[0]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn grain_round_constants_are_deterministic_and_right_shaped() {
+        let (rc_full_a, rc_partial_a) = grain::generate_round_constants(17, 8, 64);
+        let (rc_full_b, rc_partial_b) = grain::generate_round_constants(17, 8, 64);
+
+        assert_eq!(rc_full_a.len(), 8);
+        assert!(rc_full_a.iter().all(|row| row.len() == 17));
+        assert_eq!(rc_partial_a.len(), 64);
+        assert_eq!(rc_full_a, rc_full_b);
+        assert_eq!(rc_partial_a, rc_partial_b);
+    }
+
+    #[test]
+    fn grain_round_constants_differ_across_widths() {
+        let (rc_full_17, _) = grain::generate_round_constants(17, 8, 64);
+        let (rc_full_9, _) = grain::generate_round_constants(9, 8, 60);
+        assert_ne!(rc_full_17[0][0], rc_full_9[0][0]);
+    }
+
+    #[test]
+    fn cauchy_mds_has_no_vanishing_submatrix_determinant() {
+        // A Cauchy matrix is MDS iff every square submatrix is nonsingular; check
+        // that exhaustively for a small t where enumerating all submatrices is cheap.
+        let t = 4;
+        let mds = derive_mds(&seed_for_t(t), t);
+
+        for k in 1..=t {
+            for rows in combinations(t, k) {
+                for cols in combinations(t, k) {
+                    let sub: Vec<Vec<F>> = rows
+                        .iter()
+                        .map(|&r| cols.iter().map(|&c| mds[r][c]).collect())
+                        .collect();
+                    assert!(determinant(&sub).is_some(), "submatrix rows={rows:?} cols={cols:?} is singular");
+                }
+            }
+        }
+    }
+
+    /// All size-`k` subsets of `0..n`, for exhaustively enumerating submatrices.
+    fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+        if k == 0 {
+            return vec![vec![]];
+        }
+        if k > n {
+            return vec![];
+        }
+        let mut out = Vec::new();
+        for start in 0..n {
+            for mut rest in combinations(n - start - 1, k - 1) {
+                for r in rest.iter_mut() {
+                    *r += start + 1;
+                }
+                let mut combo = vec![start];
+                combo.append(&mut rest);
+                out.push(combo);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn permute_generic_matches_permute_dynamic_for_t17() {
+        let dyn_params = poseidon_params_for_width(17);
+        let gen_params: PoseidonParamsGeneric<17, RP> = poseidon_params_generic();
+
+        let mut state_dyn: Vec<F> = (0..17u64).map(F::from).collect();
+        let mut state_gen: [F; 17] = std::array::from_fn(|i| F::from(i as u64));
+
+        permute_dynamic(&mut state_dyn, &dyn_params);
+        permute_generic(&mut state_gen, &gen_params);
+
+        assert_eq!(state_dyn, state_gen.to_vec());
+    }
+
+    #[test]
+    fn permute_generic_matches_permute_dynamic_for_t9() {
+        let dyn_params = poseidon_params_for_width(9);
+        let gen_params: PoseidonParamsGeneric<9, RP_9> = poseidon_params_generic();
+
+        let mut state_dyn: Vec<F> = (0..9u64).map(F::from).collect();
+        let mut state_gen: [F; 9] = std::array::from_fn(|i| F::from(i as u64));
+
+        permute_dynamic(&mut state_dyn, &dyn_params);
+        permute_generic(&mut state_gen, &gen_params);
+
+        assert_eq!(state_dyn, state_gen.to_vec());
+    }
+
+    /// The pre-optimization partial-round loop: a full `mds_mul_dynamic_in_place`
+    /// every partial round, kept here only so `partial_round_sparse_mds_matches_dense`
+    /// has something to check the O(t) sparse path against.
+    fn permute_dynamic_dense_reference(state: &mut [F], params: &PoseidonParamsDynamic) {
+        let t = params.t;
+        assert_eq!(state.len(), t);
+
+        let rf = params.rounds_full;
+        let rp = params.rounds_partial;
+        let rf_half = rf / 2;
+
+        for r in 0..rf_half {
+            for i in 0..t {
+                state[i] += params.rc_full[r][i];
+            }
+            for i in 0..t {
+                state[i] = sbox5(state[i]);
+            }
+            mds_mul_dynamic_in_place(&params.mds, state);
+        }
+
+        for r in 0..rp {
+            state[0] += params.rc_partial[r];
+            state[0] = sbox5(state[0]);
+            mds_mul_dynamic_in_place(&params.mds, state);
+        }
+
+        for r in rf_half..rf {
+            for i in 0..t {
+                state[i] += params.rc_full[r][i];
+            }
+            for i in 0..t {
+                state[i] = sbox5(state[i]);
+            }
+            mds_mul_dynamic_in_place(&params.mds, state);
+        }
+    }
+
+    #[test]
+    fn partial_round_sparse_mds_matches_dense() {
+        for &t in &[17usize, 9] {
+            let params = poseidon_params_for_width(t);
+
+            let mut state_sparse: Vec<F> = (0..t as u64).map(F::from).collect();
+            let mut state_dense = state_sparse.clone();
+
+            permute_dynamic(&mut state_sparse, &params);
+            permute_dynamic_dense_reference(&mut state_dense, &params);
+
+            assert_eq!(state_sparse, state_dense, "sparse/dense mismatch at t={t}");
+        }
+    }
+
+    #[test]
+    fn hash_with_ds_dynamic_matches_sponge_squeeze_one() {
+        let params = poseidon_params_for_width(9);
+        let ds_fields = [F::from(11u64), F::from(22u64)];
+        let inputs = [F::from(1u64), F::from(2u64), F::from(3u64)];
+
+        let digest = hash_with_ds_dynamic(&ds_fields, &inputs, &params);
+
+        let mut sponge = Sponge::new(&params);
+        sponge.absorb(&ds_fields);
+        sponge.absorb(&inputs);
+        sponge.pad_to_boundary();
+        let squeezed = sponge.squeeze(1);
+
+        assert_eq!(digest, squeezed[0]);
+    }
+
+    #[test]
+    fn sponge_squeeze_past_rate_matches_manual_permutes() {
+        let params = poseidon_params_for_width(9);
+        let rate = params.rate;
+
+        let mut sponge = Sponge::new(&params);
+        sponge.absorb(&[F::from(7u64)]);
+        let got = sponge.squeeze(rate + 3);
+
+        // Replay the same absorption by hand: one block absorbed, then read `rate`
+        // lanes, permute, and read 3 more.
+        let mut state = vec![F::zero(); params.t];
+        state[0] += F::from(7u64);
+        permute_dynamic(&mut state, &params);
+        let mut expected: Vec<F> = state[..rate].to_vec();
+        permute_dynamic(&mut state, &params);
+        expected.extend_from_slice(&state[..3]);
+
+        assert_eq!(got, expected);
+    }
+
+    #[test]
+    fn sponge_absorb_after_squeeze_repermutes_before_mixing_in() {
+        let params = poseidon_params_for_width(9);
+
+        let mut a = Sponge::new(&params);
+        a.absorb(&[F::from(5u64)]);
+        let _ = a.squeeze(1);
+        a.absorb(&[F::from(6u64)]);
+        let out_a = a.squeeze(1);
+
+        // Absorbing straight through without an intervening squeeze must diverge,
+        // since the squeeze-then-absorb path re-permutes the state in between.
+        let mut b = Sponge::new(&params);
+        b.absorb(&[F::from(5u64), F::from(6u64)]);
+        let out_b = b.squeeze(1);
+
+        assert_ne!(out_a, out_b);
+    }
+}
@@ -0,0 +1,117 @@
+use ark_pallas::Fr as F;
+use ark_serialize::{CanonicalDeserialize, Compress, Validate};
+use deep_ali::fri::{deep_fri_prove, deep_fri_verify, DeepAliRealBuilder, DeepFriParams, DeepFriProof};
+use merkle::SiblingOrder;
+use wasm_bindgen::prelude::*;
+
+// Browser entry points for the plain and DEEP-FRI pipelines.
+//
+// Regenerating a verifying key or `DeepFriParams` in the browser is expensive, so every
+// function here takes them pre-serialized: generate once off the main thread (or
+// offline) and serve the bytes statically, exactly like the existing public-parameter
+// hand-off elsewhere in the repo. Prove/verify are `async fn`s so `wasm-bindgen` (via
+// `wasm-bindgen-futures` on the JS side) schedules the CPU-heavy work without blocking
+// the page's main thread.
+
+fn js_err(msg: impl Into<String>) -> JsValue {
+    JsValue::from_str(&msg.into())
+}
+
+fn decode_field_vec(cur: &mut &[u8]) -> Result<Vec<F>, JsValue> {
+    Vec::<F>::deserialize_with_mode(cur, Compress::Yes, Validate::Yes)
+        .map_err(|e| js_err(format!("malformed field vector: {e}")))
+}
+
+// A·S + E − T, evaluated pointwise over whatever domain the caller sampled (a,s,e,t) on.
+// This is the one-column Φ the non-generic DEEP-ALI merge used before `DeepAliBuilder`
+// was generalized to arbitrary constraint columns (see `deep_ali::DeepAliBuilder`).
+fn mlwe_phi_column(a: &[F], s: &[F], e: &[F], t: &[F]) -> Result<Vec<F>, JsValue> {
+    if s.len() != a.len() || e.len() != a.len() || t.len() != a.len() {
+        return Err(js_err("a, s, e, t must all have the same length"));
+    }
+    Ok(a.iter()
+        .zip(s)
+        .zip(e)
+        .zip(t)
+        .map(|(((&ai, &si), &ei), &ti)| ai * si + ei - ti)
+        .collect())
+}
+
+/// Decode the wire witness as four length-prefixed field vectors `a, s, e, t` in that
+/// order, matching the encoding `Vec<F>`'s `CanonicalSerialize` impl produces.
+fn decode_mlwe_witness(witness_bytes: &[u8]) -> Result<(Vec<F>, Vec<F>, Vec<F>, Vec<F>), JsValue> {
+    let mut cur = witness_bytes;
+    let a = decode_field_vec(&mut cur)?;
+    let s = decode_field_vec(&mut cur)?;
+    let e = decode_field_vec(&mut cur)?;
+    let t = decode_field_vec(&mut cur)?;
+    Ok((a, s, e, t))
+}
+
+#[wasm_bindgen]
+pub async fn deep_fri_prove_wasm(params_bytes: &[u8], witness_bytes: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let params = DeepFriParams::deserialize(params_bytes).ok_or_else(|| js_err("malformed DeepFriParams"))?;
+    let (a, s, e, t) = decode_mlwe_witness(witness_bytes)?;
+    let n0 = a.len();
+    let columns = vec![mlwe_phi_column(&a, &s, &e, &t)?];
+
+    let proof = deep_fri_prove(&DeepAliRealBuilder::default(), &columns, n0, &params);
+    Ok(proof.serialize(SiblingOrder::DepthFirst))
+}
+
+#[wasm_bindgen]
+pub async fn deep_fri_verify_wasm(params_bytes: &[u8], proof_bytes: &[u8]) -> Result<bool, JsValue> {
+    let params = DeepFriParams::deserialize(params_bytes).ok_or_else(|| js_err("malformed DeepFriParams"))?;
+    let proof = DeepFriProof::deserialize(proof_bytes).ok_or_else(|| js_err("malformed DeepFriProof"))?;
+    Ok(deep_fri_verify(&params, &proof).is_ok())
+}
+
+// ---- Plain pipeline ----
+//
+// Mirrors the entry points `channel::{build_vk_plain, prove_plain, verify_plain}` that
+// the `e2e_plain` criterion bench already drives (see
+// `channel/benches/end_to_end.rs`), bincode-encoded the same way that bench reports
+// vk/proof sizes. `build_vk_plain` itself isn't exposed here: the VK is meant to be
+// built once off the main thread and shipped to the browser as a static blob, not
+// regenerated per call.
+
+#[wasm_bindgen]
+pub async fn prove_plain_wasm(vk_bytes: &[u8], witness_bytes: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let vk = bincode::deserialize(vk_bytes).map_err(|e| js_err(format!("malformed verifying key: {e}")))?;
+    let witness = decode_field_vec(&mut &witness_bytes[..])?;
+    let proof = channel::prove_plain(&vk, &witness);
+    bincode::serialize(&proof).map_err(|e| js_err(format!("failed to serialize proof: {e}")))
+}
+
+#[wasm_bindgen]
+pub async fn verify_plain_wasm(vk_bytes: &[u8], proof_bytes: &[u8]) -> Result<bool, JsValue> {
+    let vk = bincode::deserialize(vk_bytes).map_err(|e| js_err(format!("malformed verifying key: {e}")))?;
+    let proof = bincode::deserialize(proof_bytes).map_err(|e| js_err(format!("malformed proof: {e}")))?;
+    Ok(channel::verify_plain(&vk, &proof))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mlwe_phi_column_matches_pointwise_a_s_e_t() {
+        let a = vec![F::from(2u64), F::from(3u64)];
+        let s = vec![F::from(5u64), F::from(7u64)];
+        let e = vec![F::from(1u64), F::from(1u64)];
+        let t = vec![F::from(0u64), F::from(4u64)];
+
+        let phi = mlwe_phi_column(&a, &s, &e, &t).unwrap();
+        assert_eq!(phi, vec![F::from(11u64), F::from(18u64)]);
+    }
+
+    #[test]
+    fn mlwe_phi_column_rejects_mismatched_lengths() {
+        let a = vec![F::from(1u64), F::from(2u64)];
+        let s = vec![F::from(1u64)];
+        let e = vec![F::from(1u64), F::from(1u64)];
+        let t = vec![F::from(1u64), F::from(1u64)];
+
+        assert!(mlwe_phi_column(&a, &s, &e, &t).is_err());
+    }
+}